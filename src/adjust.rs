@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::RecorderError;
+use crate::storage;
+
+const ADJUSTMENTS_KEY_PREFIX: &str = "demrec.camera_adjust.";
+const LUT_KEY_PREFIX: &str = "demrec.lut.";
+
+/// Color and exposure corrections for one camera. Sliders are centered on
+/// zero, so the default leaves the picture untouched.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CameraAdjustments {
+    /// Exposure in stops (-1 to 1)
+    pub brightness: f64,
+    /// -1 (flat) to 1 (punchy)
+    pub contrast: f64,
+    /// -1 (grayscale) to 1 (double)
+    pub saturation: f64,
+    /// -1 (cooler/blue) to 1 (warmer/orange)
+    pub temperature: f64,
+    /// Corner darkening (0 to 1)
+    pub vignette: f64,
+    /// Name of the 3D LUT applied last, stored separately (see `load_lut`)
+    pub lut_name: Option<String>,
+}
+
+/// A 3D color lookup table parsed from an Adobe/Resolve .cube file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut {
+    size: usize,
+    /// Output colors, red index changing fastest
+    table: Vec<[f32; 3]>,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+}
+
+impl Lut {
+    pub fn parse(text: &str) -> Result<Lut, String> {
+        let mut size = 0;
+        let mut table = Vec::new();
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+
+        let parse_triple = |values: &[&str]| -> Result<[f32; 3], String> {
+            if values.len() != 3 {
+                return Err(format!("expected 3 values, got {}", values.len()));
+            }
+            let mut triple = [0.0; 3];
+            for (slot, value) in triple.iter_mut().zip(values) {
+                *slot = value.parse().map_err(|_| format!("invalid number `{}`", value))?;
+            }
+            Ok(triple)
+        };
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let context = |error: String| format!("line {}: {}", line_number + 1, error);
+
+            match parts[0] {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    size = parts
+                        .get(1)
+                        .and_then(|value| value.parse().ok())
+                        .filter(|&size| (2..=256).contains(&size))
+                        .ok_or_else(|| context("invalid LUT_3D_SIZE".to_string()))?;
+                }
+                "LUT_1D_SIZE" => return Err(context("1D LUTs are not supported".to_string())),
+                "DOMAIN_MIN" => domain_min = parse_triple(&parts[1..]).map_err(context)?,
+                "DOMAIN_MAX" => domain_max = parse_triple(&parts[1..]).map_err(context)?,
+                keyword if keyword.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) => {
+                    // Unknown keywords are allowed by the format
+                }
+                _ => table.push(parse_triple(&parts).map_err(context)?),
+            }
+        }
+
+        if size == 0 {
+            return Err("missing LUT_3D_SIZE".to_string());
+        }
+        if table.len() != size * size * size {
+            return Err(format!("expected {} entries, found {}", size * size * size, table.len()));
+        }
+
+        Ok(Lut {
+            size,
+            table,
+            domain_min,
+            domain_max,
+        })
+    }
+
+    /// Look up a color (each channel 0-1) with trilinear interpolation
+    pub fn apply(&self, color: [f32; 3]) -> [f32; 3] {
+        let max_index = (self.size - 1) as f32;
+        let mut base = [0usize; 3];
+        let mut fraction = [0.0f32; 3];
+        for channel in 0..3 {
+            let range = (self.domain_max[channel] - self.domain_min[channel]).max(f32::EPSILON);
+            let position = ((color[channel] - self.domain_min[channel]) / range).clamp(0.0, 1.0) * max_index;
+            base[channel] = (position.floor() as usize).min(self.size - 2);
+            fraction[channel] = position - base[channel] as f32;
+        }
+
+        let entry = |r: usize, g: usize, b: usize| self.table[r + g * self.size + b * self.size * self.size];
+        let mut result = [0.0; 3];
+        for (dr, weight_r) in [(0, 1.0 - fraction[0]), (1, fraction[0])] {
+            for (dg, weight_g) in [(0, 1.0 - fraction[1]), (1, fraction[1])] {
+                for (db, weight_b) in [(0, 1.0 - fraction[2]), (1, fraction[2])] {
+                    let weight = weight_r * weight_g * weight_b;
+                    let value = entry(base[0] + dr, base[1] + dg, base[2] + db);
+                    for channel in 0..3 {
+                        result[channel] += value[channel] * weight;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl CameraAdjustments {
+    /// Whether applying these adjustments would leave the picture unchanged
+    pub fn is_neutral(&self) -> bool {
+        self.brightness == 0.0
+            && self.contrast == 0.0
+            && self.saturation == 0.0
+            && self.temperature == 0.0
+            && self.vignette == 0.0
+            && self.lut_name.is_none()
+    }
+
+    /// Apply the adjustments to an RGBA buffer of the given size
+    pub fn apply(&self, pixels: &mut [u8], width: u32, height: u32, lut: Option<&Lut>) {
+        // Exposure, contrast and temperature only depend on the channel value,
+        // so they are baked into one table per channel
+        let exposure = 2f32.powf(self.brightness as f32);
+        let contrast = 1.0 + self.contrast as f32;
+        let warmth = [0.08 * self.temperature as f32, 0.0, -0.08 * self.temperature as f32];
+        let mut tone = [[0.0f32; 256]; 3];
+        for (channel, table) in tone.iter_mut().enumerate() {
+            for (value, slot) in table.iter_mut().enumerate() {
+                let linear = value as f32 / 255.0 * exposure;
+                *slot = (linear - 0.5) * contrast + 0.5 + warmth[channel];
+            }
+        }
+
+        let saturation = 1.0 + self.saturation as f32;
+        let vignette = self.vignette as f32;
+        let center_x = width as f32 / 2.0;
+        let center_y = height as f32 / 2.0;
+        let max_distance = (center_x * center_x + center_y * center_y).sqrt().max(1.0);
+
+        for (index, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let mut color = [tone[0][pixel[0] as usize], tone[1][pixel[1] as usize], tone[2][pixel[2] as usize]];
+
+            if saturation != 1.0 {
+                let luma = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+                for value in &mut color {
+                    *value = luma + (*value - luma) * saturation;
+                }
+            }
+
+            if vignette > 0.0 {
+                let x = (index as u32 % width) as f32 - center_x;
+                let y = (index as u32 / width) as f32 - center_y;
+                let distance = (x * x + y * y).sqrt() / max_distance;
+                let falloff = 1.0 - vignette * distance * distance;
+                for value in &mut color {
+                    *value *= falloff;
+                }
+            }
+
+            if let Some(lut) = lut {
+                color = lut.apply(color.map(|value| value.clamp(0.0, 1.0)));
+            }
+
+            for channel in 0..3 {
+                pixel[channel] = (color[channel].clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+pub fn load_adjustments(device_id: &str) -> CameraAdjustments {
+    storage::load_json(&format!("{}{}", ADJUSTMENTS_KEY_PREFIX, device_id)).unwrap_or_default()
+}
+
+pub fn save_adjustments(device_id: &str, adjustments: &CameraAdjustments) {
+    storage::save_json(&format!("{}{}", ADJUSTMENTS_KEY_PREFIX, device_id), adjustments);
+}
+
+/// Load a LUT previously stored with `save_lut`
+pub fn load_lut(name: &str) -> Option<Lut> {
+    let text = storage::load_json::<String>(&format!("{}{}", LUT_KEY_PREFIX, name))?;
+    Lut::parse(&text).ok()
+}
+
+/// Keep the .cube source in browser storage so it can be reused per camera
+pub fn save_lut(name: &str, text: &str) -> Result<(), RecorderError> {
+    storage::try_save_json(&format!("{}{}", LUT_KEY_PREFIX, name), &text, "the LUT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An identity .cube of the given size, red changing fastest
+    fn identity_cube(size: usize) -> String {
+        let mut text = format!("TITLE \"Identity\"\n# comment\nLUT_3D_SIZE {size}\n\n");
+        let step = |index: usize| index as f32 / (size - 1) as f32;
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    text.push_str(&format!("{} {} {}\n", step(r), step(g), step(b)));
+                }
+            }
+        }
+        text
+    }
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn parses_an_identity_cube() {
+        let lut = Lut::parse(&identity_cube(3)).unwrap();
+        assert_eq!(lut.size, 3);
+        assert_eq!(lut.table.len(), 27);
+        assert_eq!(lut.table[1], [0.5, 0.0, 0.0]);
+        assert_eq!(lut.table[3], [0.0, 0.5, 0.0]);
+        assert_eq!(lut.table[9], [0.0, 0.0, 0.5]);
+        assert_eq!((lut.domain_min, lut.domain_max), ([0.0; 3], [1.0; 3]));
+    }
+
+    #[test]
+    fn rejects_invalid_cubes() {
+        let two = identity_cube(2);
+        let cases = [
+            ("LUT_3D_SIZE 1\n0 0 0\n", "invalid LUT_3D_SIZE"),
+            ("LUT_3D_SIZE big\n", "invalid LUT_3D_SIZE"),
+            ("0 0 0\n", "missing LUT_3D_SIZE"),
+            (&two[..two.len() - 6], "expected 8 entries, found 7"),
+            ("LUT_1D_SIZE 16\n", "1D LUTs are not supported"),
+            ("DOMAIN_MIN 0 0\nLUT_3D_SIZE 2\n", "expected 3 values, got 2"),
+            ("DOMAIN_MAX 1 1 x\nLUT_3D_SIZE 2\n", "invalid number `x`"),
+        ];
+        for (text, expected) in cases {
+            let error = Lut::parse(text).unwrap_err();
+            assert!(error.contains(expected), "{text:?}: {error}");
+        }
+    }
+
+    #[test]
+    fn identity_lut_round_trips() {
+        let lut = Lut::parse(&identity_cube(5)).unwrap();
+        for color in [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [0.2, 0.7, 0.45], [0.9, 0.1, 0.33]] {
+            assert_close(lut.apply(color), color);
+        }
+    }
+
+    #[test]
+    fn lut_lookups_respect_the_domain() {
+        let text = identity_cube(2).replace("LUT_3D_SIZE", "DOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\nLUT_3D_SIZE");
+        let lut = Lut::parse(&text).unwrap();
+        assert_close(lut.apply([1.0, 0.5, 2.0]), [0.5, 0.25, 1.0]);
+    }
+
+    #[test]
+    fn neutral_adjustments_leave_pixels_unchanged() {
+        let adjustments = CameraAdjustments::default();
+        assert!(adjustments.is_neutral());
+
+        let original: Vec<u8> = (0..=255).flat_map(|value| [value, 255 - value, value / 2, 255]).collect();
+        let mut pixels = original.clone();
+        adjustments.apply(&mut pixels, 16, 16, None);
+        assert_eq!(pixels, original);
+
+        // An identity LUT changes nothing either
+        let lut = Lut::parse(&identity_cube(17)).unwrap();
+        adjustments.apply(&mut pixels, 16, 16, Some(&lut));
+        assert_eq!(pixels, original);
+    }
+
+    #[test]
+    fn brightness_and_saturation_change_pixels() {
+        let mut pixels = vec![100, 50, 25, 255];
+        let brighter = CameraAdjustments {
+            brightness: 1.0,
+            ..CameraAdjustments::default()
+        };
+        brighter.apply(&mut pixels, 1, 1, None);
+        assert_eq!(pixels, [200, 100, 50, 255]);
+
+        let grayscale = CameraAdjustments {
+            saturation: -1.0,
+            ..CameraAdjustments::default()
+        };
+        grayscale.apply(&mut pixels, 1, 1, None);
+        assert!(pixels[0] == pixels[1] && pixels[1] == pixels[2]);
+    }
+}
@@ -0,0 +1,39 @@
+/// A value animated linearly from one number to another over a fixed duration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tween {
+    from: f64,
+    to: f64,
+    start_ms: f64,
+    duration_ms: f64,
+}
+
+impl Tween {
+    /// A tween that is already settled on a value
+    pub fn settled(value: f64) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start_ms: 0.0,
+            duration_ms: 0.0,
+        }
+    }
+
+    /// Value at the given time (milliseconds, same clock as `js_sys::Date::now`)
+    pub fn value(&self, now_ms: f64) -> f64 {
+        if self.duration_ms <= 0.0 {
+            return self.to;
+        }
+        let progress = ((now_ms - self.start_ms) / self.duration_ms).clamp(0.0, 1.0);
+        self.from + (self.to - self.from) * progress
+    }
+
+    /// Retarget the tween, starting from wherever it currently is
+    pub fn towards(&self, to: f64, now_ms: f64, duration_ms: f64) -> Self {
+        Self {
+            from: self.value(now_ms),
+            to,
+            start_ms: now_ms,
+            duration_ms,
+        }
+    }
+}
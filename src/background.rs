@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::error::RecorderError;
+use crate::redact::{self, RedactionRegion};
+use crate::screen::{ScreenSource, ScreenTransform};
+use crate::{image, storage};
+
+const BACKGROUND_IMAGES_KEY: &str = "demrec.background_images";
+const VIRTUAL_BACKGROUND_KEY: &str = "demrec.virtual_background";
+const LETTERBOX_FILL_KEY: &str = "demrec.letterbox_fill";
+
+/// Blur radius for the blurred screen background
+pub const SCREEN_BLUR_RADIUS: f64 = 24.0;
+
+/// What is shown behind a keyed full-frame presenter
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VirtualBackground {
+    BlurredScreen,
+    Color(String),
+    /// Index into the saved background images
+    Image(usize),
+}
+
+impl Default for VirtualBackground {
+    fn default() -> Self {
+        VirtualBackground::Color("#1f2937".to_string())
+    }
+}
+
+/// What fills the canvas around a letterboxed screen share
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LetterboxFill {
+    Color(String),
+    /// Top to bottom linear gradient between two colors
+    Gradient(String, String),
+    /// Index into the saved background images
+    Image(usize),
+    BlurredScreen,
+}
+
+impl Default for LetterboxFill {
+    fn default() -> Self {
+        LetterboxFill::Color("#000000".to_string())
+    }
+}
+
+/// A background image kept in browser storage
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundImage {
+    pub url: String,
+    pub image: web_sys::HtmlImageElement,
+}
+
+pub fn load_background_images() -> Vec<BackgroundImage> {
+    storage::load_json::<Vec<String>>(BACKGROUND_IMAGES_KEY)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|url| {
+            let image = image::load_image(&url)?;
+            Some(BackgroundImage { url, image })
+        })
+        .collect()
+}
+
+pub fn save_background_images(images: &[BackgroundImage]) -> Result<(), RecorderError> {
+    let urls: Vec<&str> = images.iter().map(|background| background.url.as_str()).collect();
+    storage::try_save_json(BACKGROUND_IMAGES_KEY, &urls, "background images")
+}
+
+pub fn load_virtual_background() -> VirtualBackground {
+    storage::load_json(VIRTUAL_BACKGROUND_KEY).unwrap_or_default()
+}
+
+pub fn save_virtual_background(background: &VirtualBackground) {
+    storage::save_json(VIRTUAL_BACKGROUND_KEY, background);
+}
+
+pub fn load_letterbox_fill() -> LetterboxFill {
+    storage::load_json(LETTERBOX_FILL_KEY).unwrap_or_default()
+}
+
+pub fn save_letterbox_fill(fill: &LetterboxFill) {
+    storage::save_json(LETTERBOX_FILL_KEY, fill);
+}
+
+/// Fill the rectangle with a vertical gradient from the top color to the bottom one
+pub fn draw_gradient(ctx: &web_sys::CanvasRenderingContext2d, top: &str, bottom: &str, dest: (f64, f64, f64, f64)) {
+    let (x, y, width, height) = dest;
+    let gradient = ctx.create_linear_gradient(x, y, x, y + height);
+    let _ = gradient.add_color_stop(0.0, top);
+    let _ = gradient.add_color_stop(1.0, bottom);
+
+    ctx.save();
+    ctx.set_fill_style_canvas_gradient(&gradient);
+    ctx.fill_rect(x, y, width, height);
+    ctx.restore();
+}
+
+/// Draw a blurred copy of the screen share zoomed to cover the rectangle.
+/// Redactions are applied before blurring so nothing hidden leaks through.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_blurred_screen(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    scratch: &web_sys::HtmlCanvasElement,
+    redact_scratch: &web_sys::HtmlCanvasElement,
+    source: &ScreenSource,
+    src: (f64, f64, f64, f64),
+    redactions: &[RedactionRegion],
+    dest: (f64, f64, f64, f64),
+) {
+    let (x, y, width, height) = dest;
+
+    // Draw at a quarter of the size, the blur hides the lost detail
+    let scratch_width = (width / 4.0).ceil().max(1.0);
+    let scratch_height = (height / 4.0).ceil().max(1.0);
+    scratch.set_width(scratch_width as u32);
+    scratch.set_height(scratch_height as u32);
+    let Ok(Some(context)) = scratch.get_context("2d") else {
+        return;
+    };
+    let Ok(scratch_ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() else {
+        return;
+    };
+
+    let Some(transform) = ScreenTransform::cover(src, scratch_width, scratch_height) else {
+        return;
+    };
+    let (scaled_width, scaled_height) = transform.dest_size();
+    source.draw(
+        &scratch_ctx,
+        src.0,
+        src.1,
+        src.2,
+        src.3,
+        transform.dest_x,
+        transform.dest_y,
+        scaled_width,
+        scaled_height,
+    );
+    redact::draw_redactions(&scratch_ctx, source, redact_scratch, redactions, &transform);
+
+    // Overdraw past the edges so the blur doesn't fade to transparent there
+    let margin = SCREEN_BLUR_RADIUS * 2.0;
+    ctx.save();
+    ctx.begin_path();
+    ctx.rect(x, y, width, height);
+    ctx.clip();
+    ctx.set_filter(&format!("blur({}px)", SCREEN_BLUR_RADIUS));
+    let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
+        scratch,
+        x - margin,
+        y - margin,
+        width + margin * 2.0,
+        height + margin * 2.0,
+    );
+    ctx.restore();
+}
@@ -0,0 +1,110 @@
+use wasm_bindgen::{Clamped, JsCast};
+
+use crate::adjust::{CameraAdjustments, Lut};
+use crate::chroma::ChromaKey;
+
+// Widest frame processed per pixel, larger layers are scaled up afterwards
+const MAX_WORK_WIDTH: f64 = 960.0;
+
+/// Create an offscreen canvas for per-pixel camera processing
+pub fn create_work_canvas() -> Option<web_sys::HtmlCanvasElement> {
+    let canvas = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+
+    // Pixels are read back every frame, let the browser keep them on the CPU
+    let options = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&options, &"willReadFrequently".into(), &true.into());
+    let _ = canvas.get_context_with_context_options("2d", &options);
+    Some(canvas)
+}
+
+fn work_context(work: &web_sys::HtmlCanvasElement, width: u32, height: u32) -> Option<web_sys::CanvasRenderingContext2d> {
+    // Resizing clears the canvas, only do it when the size changes
+    if work.width() != width || work.height() != height {
+        work.set_width(width);
+        work.set_height(height);
+    }
+    work.get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()
+}
+
+/// Draw the camera with per-pixel processing: the chroma key is computed on
+/// the raw colors, then the color adjustments are applied. The work canvas
+/// holds the processed frame before it is drawn into `dest`.
+pub fn draw_processed(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    camera_video: &web_sys::HtmlVideoElement,
+    work: &web_sys::HtmlCanvasElement,
+    key: Option<&ChromaKey>,
+    adjustments: Option<(&CameraAdjustments, Option<&Lut>)>,
+    src: (f64, f64, f64, f64),
+    dest: (f64, f64, f64, f64),
+) {
+    // Large layers are processed at reduced resolution to keep up with the frame rate
+    let scale = (MAX_WORK_WIDTH / dest.2).min(1.0);
+    let width = (dest.2 * scale).round().max(1.0) as u32;
+    let height = (dest.3 * scale).round().max(1.0) as u32;
+    let Some(work_ctx) = work_context(work, width, height) else {
+        return;
+    };
+
+    work_ctx.clear_rect(0.0, 0.0, width as f64, height as f64);
+    let _ = work_ctx.draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+        camera_video,
+        src.0,
+        src.1,
+        src.2,
+        src.3,
+        0.0,
+        0.0,
+        width as f64,
+        height as f64,
+    );
+
+    let Ok(image_data) = work_ctx.get_image_data(0.0, 0.0, width as f64, height as f64) else {
+        return;
+    };
+    let mut pixels = image_data.data();
+    if let Some(key) = key {
+        key.apply(&mut pixels);
+    }
+    if let Some((adjustments, lut)) = adjustments {
+        adjustments.apply(&mut pixels, width, height, lut);
+    }
+    if let Ok(processed) = web_sys::ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), width, height) {
+        let _ = work_ctx.put_image_data(&processed, 0.0, 0.0);
+    }
+
+    let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(work, dest.0, dest.1, dest.2, dest.3);
+}
+
+/// Read the color of one camera video pixel
+pub fn sample_color(
+    camera_video: &web_sys::HtmlVideoElement,
+    work: &web_sys::HtmlCanvasElement,
+    video_x: f64,
+    video_y: f64,
+) -> Option<(u8, u8, u8)> {
+    let work_ctx = work_context(work, 1, 1)?;
+    work_ctx
+        .draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            camera_video,
+            video_x.floor(),
+            video_y.floor(),
+            1.0,
+            1.0,
+            0.0,
+            0.0,
+            1.0,
+            1.0,
+        )
+        .ok()?;
+    let pixel = work_ctx.get_image_data(0.0, 0.0, 1.0, 1.0).ok()?.data();
+    Some((pixel[0], pixel[1], pixel[2]))
+}
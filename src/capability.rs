@@ -0,0 +1,178 @@
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Recording formats in order of preference
+pub const RECORDING_MIME_TYPES: [&str; 6] = [
+    "video/webm;codecs=vp9,opus",
+    "video/webm;codecs=vp8,opus",
+    "video/webm",
+    "video/mp4;codecs=avc1,mp4a",
+    "video/mp4;codecs=avc1",
+    "video/mp4",
+];
+
+/// What the browser can do, detected feature by feature rather than from the user agent
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Capabilities {
+    pub user_media: bool,
+    pub display_media: bool,
+    /// Tab or system audio can be captured with the screen
+    pub display_media_audio: bool,
+    pub media_recorder: bool,
+    /// Entries of `RECORDING_MIME_TYPES` the MediaRecorder accepts, in order of preference
+    pub recording_mime_types: Vec<String>,
+    /// `showSaveFilePicker`, for streaming recordings straight to disk
+    pub file_system_access: bool,
+    /// Picture-in-Picture for a video element, used for the floating preview
+    pub video_pip: bool,
+    /// Document Picture-in-Picture, for floating controls
+    pub document_pip: bool,
+    pub web_codecs: bool,
+    /// Origin private file system, for keeping recordings in the browser
+    pub opfs: bool,
+}
+
+// Whether `object` has a property `name`, including inherited ones
+fn has(object: &JsValue, name: &str) -> bool {
+    !object.is_undefined() && js_sys::Reflect::has(object, &name.into()).unwrap_or(false)
+}
+
+fn get(object: &JsValue, name: &str) -> JsValue {
+    js_sys::Reflect::get(object, &name.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+impl Capabilities {
+    pub fn detect() -> Self {
+        let Some(window) = web_sys::window() else {
+            return Self::default();
+        };
+        let window: JsValue = window.into();
+        let navigator = get(&window, "navigator");
+        let media_devices = get(&navigator, "mediaDevices");
+        let document = get(&window, "document");
+
+        // Chrome added this constraint together with tab audio capture
+        let display_media_audio = get(&media_devices, "getSupportedConstraints")
+            .dyn_ref::<js_sys::Function>()
+            .and_then(|function| function.call0(&media_devices).ok())
+            .is_some_and(|constraints| has(&constraints, "suppressLocalAudioPlayback"));
+
+        let media_recorder = has(&window, "MediaRecorder");
+        let recording_mime_types = if media_recorder {
+            RECORDING_MIME_TYPES
+                .iter()
+                .filter(|mime_type| web_sys::MediaRecorder::is_type_supported(mime_type))
+                .map(|mime_type| mime_type.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            user_media: has(&media_devices, "getUserMedia"),
+            display_media: has(&media_devices, "getDisplayMedia"),
+            display_media_audio,
+            media_recorder,
+            recording_mime_types,
+            file_system_access: has(&window, "showSaveFilePicker"),
+            video_pip: get(&document, "pictureInPictureEnabled").as_bool().unwrap_or(false),
+            document_pip: has(&window, "documentPictureInPicture"),
+            web_codecs: has(&window, "VideoEncoder"),
+            opfs: has(&get(&navigator, "storage"), "getDirectory"),
+        }
+    }
+
+    /// Format to record in, None if the browser can't record at all
+    pub fn recording_mime_type(&self) -> Option<&str> {
+        self.recording_mime_types.first().map(String::as_str)
+    }
+
+    /// Whether a recording can be made at all
+    pub fn can_record(&self) -> bool {
+        self.user_media && self.media_recorder && self.recording_mime_type().is_some()
+    }
+
+    /// One line per capability for the report page: name, whether it's there,
+    /// and what is affected without it
+    pub fn report(&self) -> Vec<(&'static str, bool, &'static str)> {
+        vec![
+            ("Camera and microphone", self.user_media, "Nothing can be recorded"),
+            ("Screen sharing", self.display_media, "Only the camera can be recorded"),
+            ("Screen audio", self.display_media_audio, "Only the microphone is recorded"),
+            ("MediaRecorder", self.media_recorder, "Nothing can be recorded"),
+            (
+                "Recording format",
+                self.recording_mime_type().is_some(),
+                "No video format the browser can encode",
+            ),
+            ("Picture-in-Picture", self.video_pip, "The floating preview is unavailable"),
+            ("Document Picture-in-Picture", self.document_pip, "Floating controls are unavailable"),
+            ("WebCodecs", self.web_codecs, "Editing recordings falls back to slower paths"),
+            ("Origin private file system", self.opfs, "Recordings can't be kept in the browser"),
+        ]
+    }
+}
+
+/// Container of a recording MIME type, without the codecs, for the recorded blob
+pub fn container_type(mime_type: &str) -> &str {
+    mime_type.split(';').next().unwrap_or(mime_type).trim()
+}
+
+/// File extension for recordings in a MIME type
+pub fn file_extension(mime_type: &str) -> &'static str {
+    if container_type(mime_type) == "video/mp4" {
+        "mp4"
+    } else {
+        "webm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_capable() -> Capabilities {
+        Capabilities {
+            user_media: true,
+            media_recorder: true,
+            recording_mime_types: vec!["video/mp4".to_string()],
+            ..Capabilities::default()
+        }
+    }
+
+    #[test]
+    fn nothing_detected_cannot_record() {
+        assert!(!Capabilities::default().can_record());
+        assert_eq!(Capabilities::default().recording_mime_type(), None);
+    }
+
+    #[test]
+    fn records_without_optional_features() {
+        let capabilities = recording_capable();
+        assert!(capabilities.can_record());
+        assert_eq!(capabilities.recording_mime_type(), Some("video/mp4"));
+    }
+
+    #[test]
+    fn needs_a_format() {
+        let capabilities = Capabilities {
+            recording_mime_types: Vec::new(),
+            ..recording_capable()
+        };
+        assert!(!capabilities.can_record());
+    }
+
+    #[test]
+    fn names_files_after_the_container() {
+        assert_eq!(container_type("video/webm;codecs=vp8,opus"), "video/webm");
+        assert_eq!(file_extension("video/webm;codecs=vp9,opus"), "webm");
+        assert_eq!(file_extension("video/mp4;codecs=avc1,mp4a"), "mp4");
+        assert_eq!(file_extension("video/mp4"), "mp4");
+    }
+
+    #[test]
+    fn report_lists_missing_features() {
+        let report = recording_capable().report();
+        assert!(report.iter().any(|(name, supported, _)| *name == "Origin private file system" && !supported));
+        assert!(report.iter().any(|(name, supported, _)| *name == "MediaRecorder" && *supported));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const CHROMA_KEY: &str = "demrec.chroma_key";
+
+/// Green-screen settings for the camera layer
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChromaKey {
+    pub enabled: bool,
+    pub key_color: (u8, u8, u8),
+    /// Chroma distance below which pixels are fully transparent (0-1)
+    pub tolerance: f64,
+    /// Width of the partially transparent edge above the tolerance (0-1)
+    pub softness: f64,
+    /// How strongly the key color is removed from what remains (0-1)
+    pub spill: f64,
+}
+
+impl Default for ChromaKey {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_color: (0, 177, 64),
+            tolerance: 0.12,
+            softness: 0.08,
+            spill: 0.6,
+        }
+    }
+}
+
+// Blue/red difference chroma of an RGB color, each roughly in -0.5..0.5
+fn chroma(r: f64, g: f64, b: f64) -> (f64, f64) {
+    let cb = -0.168736 * r - 0.331264 * g + 0.5 * b;
+    let cr = 0.5 * r - 0.418688 * g - 0.081312 * b;
+    (cb / 255.0, cr / 255.0)
+}
+
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+impl ChromaKey {
+    /// Key out pixels of an RGBA buffer that are close to the key color
+    pub fn apply(&self, pixels: &mut [u8]) {
+        let (key_r, key_g, key_b) = self.key_color;
+        let key_chroma = chroma(key_r as f64, key_g as f64, key_b as f64);
+
+        // Spill is removed from whichever channel dominates the key color
+        let dominant = if key_g >= key_r && key_g >= key_b {
+            1
+        } else if key_b >= key_r {
+            2
+        } else {
+            0
+        };
+
+        for pixel in pixels.chunks_exact_mut(4) {
+            let (r, g, b) = (pixel[0] as f64, pixel[1] as f64, pixel[2] as f64);
+            let (cb, cr) = chroma(r, g, b);
+            let distance = ((cb - key_chroma.0).powi(2) + (cr - key_chroma.1).powi(2)).sqrt();
+
+            let alpha = smoothstep(self.tolerance, self.tolerance + self.softness, distance);
+            pixel[3] = (pixel[3] as f64 * alpha).round() as u8;
+
+            if self.spill > 0.0 && pixel[3] > 0 {
+                let others = match dominant {
+                    0 => g.max(b),
+                    1 => r.max(b),
+                    _ => r.max(g),
+                };
+                let excess = pixel[dominant] as f64 - others;
+                if excess > 0.0 {
+                    pixel[dominant] = (pixel[dominant] as f64 - excess * self.spill).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+pub fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub fn from_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+pub fn load_chroma_key() -> ChromaKey {
+    storage::load_json(CHROMA_KEY).unwrap_or_default()
+}
+
+pub fn save_chroma_key(key: &ChromaKey) {
+    storage::save_json(CHROMA_KEY, key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyed(key: &ChromaKey, (r, g, b): (u8, u8, u8)) -> [u8; 4] {
+        let mut pixel = [r, g, b, 255];
+        key.apply(&mut pixel);
+        pixel
+    }
+
+    fn green_screen() -> ChromaKey {
+        ChromaKey {
+            enabled: true,
+            spill: 0.0,
+            ..ChromaKey::default()
+        }
+    }
+
+    #[test]
+    fn keys_out_the_key_color() {
+        let key = green_screen();
+        assert_eq!(keyed(&key, key.key_color)[3], 0);
+        // Darker and lighter shades of the same green are keyed too
+        assert_eq!(keyed(&key, (10, 185, 72))[3], 0);
+    }
+
+    #[test]
+    fn keeps_colors_far_from_the_key() {
+        let key = green_screen();
+        for color in [(200, 30, 30), (240, 200, 180), (30, 40, 200), (128, 128, 128)] {
+            let (r, g, b) = color;
+            assert_eq!(keyed(&key, color), [r, g, b, 255], "{color:?}");
+        }
+    }
+
+    #[test]
+    fn softness_fades_the_edge() {
+        // Gray has no chroma, so its distance from the key is the key's own chroma
+        let mut key = green_screen();
+        let (key_r, key_g, key_b) = key.key_color;
+        let (cb, cr) = chroma(key_r as f64, key_g as f64, key_b as f64);
+        let distance = (cb * cb + cr * cr).sqrt();
+        key.tolerance = distance / 2.0;
+        key.softness = distance;
+        assert!((127..=128).contains(&keyed(&key, (128, 128, 128))[3]));
+
+        // Without softness the edge is hard
+        key.tolerance = distance - 0.01;
+        key.softness = 0.0;
+        assert_eq!(keyed(&key, (128, 128, 128))[3], 255);
+        key.tolerance = distance + 0.01;
+        assert_eq!(keyed(&key, (128, 128, 128))[3], 0);
+    }
+
+    #[test]
+    fn spill_pulls_the_key_channel_down() {
+        let key = ChromaKey {
+            spill: 1.0,
+            ..green_screen()
+        };
+        // A greenish skin tone far enough from the key to stay opaque
+        assert_eq!(keyed(&key, (200, 220, 150)), [200, 200, 150, 255]);
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(from_hex(&to_hex((0, 177, 64))), Some((0, 177, 64)));
+        assert_eq!(from_hex("00b140"), None);
+        assert_eq!(from_hex("#00b14"), None);
+    }
+}
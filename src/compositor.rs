@@ -0,0 +1,617 @@
+use crate::adjust::{CameraAdjustments, Lut};
+use crate::background::{self, BackgroundImage, LetterboxFill, VirtualBackground};
+use crate::chroma::ChromaKey;
+use crate::curtain::Curtain;
+use crate::frame::{self, FrameStyle};
+use crate::privacy::{self, CameraPrivacy};
+use crate::redact::{self, RedactionRegion};
+use crate::scene::SceneTransition;
+use crate::screen::{ScreenSource, ScreenTransform};
+use crate::geometry::selection_rect;
+use crate::{camera, geometry, image};
+
+/// Stacking order of the built-in layers, lowest is drawn first
+pub mod z {
+    pub const BELOW: i32 = 0;
+    pub const ABOVE: i32 = 10;
+    pub const SCENE_TRANSITION: i32 = 20;
+    pub const CROP_SELECTION: i32 = 30;
+    pub const REDACT_SELECTION: i32 = 31;
+    pub const POINTER: i32 = 40;
+    pub const CURTAIN: i32 = 50;
+    pub const COUNTDOWN: i32 = 60;
+}
+
+/// Identifies a layer when routing mouse interaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerId {
+    Screen,
+    Camera,
+    SceneTransition,
+    CropSelection,
+    RedactSelection,
+    Pointer,
+    Curtain,
+    Countdown,
+}
+
+/// Something drawn into the recorded canvas. The compositor draws visible
+/// layers from the lowest z-order up and routes the mouse to the topmost one.
+pub trait Layer {
+    fn id(&self) -> LayerId;
+
+    fn z_order(&self) -> i32;
+
+    fn is_visible(&self) -> bool;
+
+    /// Area of the canvas the layer covers (x, y, width, height), None if it
+    /// has no fixed area
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)>;
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64);
+
+    /// Whether the layer takes mouse interaction at a canvas point
+    fn hit_test(&self, x: f64, y: f64) -> bool {
+        self.bounds()
+            .is_some_and(|(left, top, width, height)| x >= left && x <= left + width && y >= top && y <= top + height)
+    }
+}
+
+/// The stack of layers making up one frame
+#[derive(Default)]
+pub struct Compositor<'a> {
+    layers: Vec<Box<dyn Layer + 'a>>,
+}
+
+impl<'a> Compositor<'a> {
+    pub fn push(&mut self, layer: impl Layer + 'a) {
+        self.layers.push(Box::new(layer));
+    }
+
+    // Layers from the bottom of the stack to the top
+    fn ordered(&self) -> Vec<&(dyn Layer + 'a)> {
+        let mut layers: Vec<&(dyn Layer + 'a)> = self.layers.iter().map(|layer| layer.as_ref()).collect();
+        layers.sort_by_key(|layer| layer.z_order());
+        layers
+    }
+
+    pub fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        for layer in self.ordered() {
+            if layer.is_visible() {
+                layer.draw(ctx, width, height);
+            }
+        }
+    }
+
+    /// Topmost visible layer taking interaction at a canvas point
+    pub fn hit_test(&self, x: f64, y: f64) -> Option<LayerId> {
+        self.ordered()
+            .into_iter()
+            .rev()
+            .find(|layer| layer.is_visible() && layer.hit_test(x, y))
+            .map(|layer| layer.id())
+    }
+}
+
+/// The screen share fitted into its layout area, framed and redacted. The
+/// letterbox fill covers the whole canvas when the screen is the main layer.
+pub struct ScreenLayer<'a> {
+    pub screen: Option<(ScreenSource, ScreenTransform)>,
+    pub opacity: f64,
+    pub z_order: i32,
+    pub fill_background: bool,
+    pub frame_style: FrameStyle,
+    pub letterbox_fill: &'a LetterboxFill,
+    pub background_images: &'a [BackgroundImage],
+    pub redactions: &'a [RedactionRegion],
+    pub background_scratch: Option<web_sys::HtmlCanvasElement>,
+    pub redact_scratch: Option<web_sys::HtmlCanvasElement>,
+}
+
+impl Layer for ScreenLayer<'_> {
+    fn id(&self) -> LayerId {
+        LayerId::Screen
+    }
+
+    fn z_order(&self) -> i32 {
+        self.z_order
+    }
+
+    fn is_visible(&self) -> bool {
+        self.screen.is_some() && self.opacity > 0.0
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.screen.as_ref().map(|(_, transform)| transform.dest_rect())
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        let Some((source, transform)) = &self.screen else {
+            return;
+        };
+        let (src_x, src_y, src_width, src_height) = transform.src;
+        let (scaled_width, scaled_height) = transform.dest_size();
+
+        ctx.save();
+        ctx.set_global_alpha(self.opacity);
+
+        // Fill the letterbox bars (or the background around a styled frame)
+        let style = self.frame_style;
+        let covers_canvas = transform.dest_x <= 0.5
+            && transform.dest_y <= 0.5
+            && transform.dest_x + scaled_width >= width - 0.5
+            && transform.dest_y + scaled_height >= height - 0.5;
+        if self.fill_background && (style.enabled || !covers_canvas) {
+            let viewport = (0.0, 0.0, width, height);
+            match self.letterbox_fill {
+                LetterboxFill::Color(color) => {
+                    ctx.set_fill_style_str(color);
+                    ctx.fill_rect(0.0, 0.0, width, height);
+                }
+                LetterboxFill::Gradient(top, bottom) => {
+                    background::draw_gradient(ctx, top, bottom, viewport);
+                }
+                LetterboxFill::Image(index) => {
+                    if let Some(background) = self.background_images.get(*index) {
+                        image::draw_cover(ctx, &background.image, 0.0, 0.0, width, height);
+                    }
+                }
+                LetterboxFill::BlurredScreen => {
+                    if let (Some(scratch), Some(redact_scratch)) = (&self.background_scratch, &self.redact_scratch) {
+                        background::draw_blurred_screen(
+                            ctx,
+                            scratch,
+                            redact_scratch,
+                            source,
+                            transform.src,
+                            self.redactions,
+                            viewport,
+                        );
+                    }
+                }
+            }
+        }
+
+        if style.enabled {
+            style.draw_back(ctx, transform.dest_rect());
+            style.clip_screen(ctx, transform.dest_rect());
+        }
+
+        source.draw(
+            ctx,
+            src_x,
+            src_y,
+            src_width,
+            src_height,
+            transform.dest_x,
+            transform.dest_y,
+            scaled_width,
+            scaled_height,
+        );
+
+        // Hide redacted regions before anything is drawn on top
+        if let Some(scratch) = &self.redact_scratch {
+            redact::draw_redactions(ctx, source, scratch, self.redactions, transform);
+        }
+        ctx.restore();
+    }
+}
+
+/// How the camera picture is processed before it is drawn
+pub struct CameraLook<'a> {
+    pub zoom: f64,
+    /// Privacy effect while the camera is hidden
+    pub privacy: Option<CameraPrivacy>,
+    pub avatar: Option<&'a web_sys::HtmlImageElement>,
+    pub chroma_key: ChromaKey,
+    pub adjustments: &'a CameraAdjustments,
+    pub lut: Option<&'a Lut>,
+    pub scratch: Option<web_sys::HtmlCanvasElement>,
+    pub work: Option<web_sys::HtmlCanvasElement>,
+}
+
+impl CameraLook<'_> {
+    /// Draw the camera into a rectangle, mirrored, with privacy and keying applied
+    pub fn draw(
+        &self,
+        ctx: &web_sys::CanvasRenderingContext2d,
+        camera_video: &web_sys::HtmlVideoElement,
+        dest: (f64, f64, f64, f64),
+    ) {
+        if self.privacy == Some(CameraPrivacy::Avatar) {
+            // Avatar replaces the camera (not mirrored)
+            privacy::draw_avatar(ctx, self.avatar, dest);
+            return;
+        }
+
+        ctx.save();
+
+        // Flip horizontally for mirror effect
+        let _ = ctx.translate(dest.0 + dest.2 / 2.0, dest.1 + dest.3 / 2.0);
+        let _ = ctx.scale(-1.0, 1.0);
+        let _ = ctx.translate(-(dest.0 + dest.2 / 2.0), -(dest.1 + dest.3 / 2.0));
+
+        // Draw camera video with zoom/crop and aspect ratio correction
+        let video_width = camera_video.video_width() as f64;
+        let video_height = camera_video.video_height() as f64;
+
+        if video_width > 0.0 && video_height > 0.0 {
+            let src = geometry::camera_source_rect(video_width, video_height, self.zoom, dest.2 / dest.3);
+            let key = &self.chroma_key;
+            let adjustments = self.adjustments;
+
+            if let Some(privacy) = self.privacy {
+                // Hide the presenter while keeping the camera running
+                if let Some(scratch) = &self.scratch {
+                    privacy::draw_private_camera(ctx, camera_video, scratch, privacy, src, dest);
+                }
+            } else if key.enabled || !adjustments.is_neutral() {
+                // Green screen keys out the background so what's behind shows through,
+                // color adjustments are applied to what remains
+                if let Some(work) = &self.work {
+                    camera::draw_processed(
+                        ctx,
+                        camera_video,
+                        work,
+                        key.enabled.then_some(key),
+                        (!adjustments.is_neutral()).then_some((adjustments, self.lut)),
+                        src,
+                        dest,
+                    );
+                }
+            } else {
+                // Draw the cropped video to fill the camera area exactly
+                let _ = ctx.draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    camera_video,
+                    src.0,
+                    src.1,
+                    src.2,
+                    src.3,
+                    dest.0,
+                    dest.1,
+                    dest.2,
+                    dest.3,
+                );
+            }
+        }
+
+        ctx.restore();
+    }
+}
+
+/// The camera in its layout area, over the virtual background when it fills the frame
+pub struct CameraLayer<'a> {
+    /// None until the camera is running and has a frame
+    pub video: Option<web_sys::HtmlVideoElement>,
+    pub rect: (f64, f64, f64, f64),
+    pub corner_radius: f64,
+    pub opacity: f64,
+    pub z_order: i32,
+    /// Whether the camera can be dragged and resized (only as a bubble)
+    pub interactive: bool,
+    pub look: CameraLook<'a>,
+    pub virtual_background: Option<&'a VirtualBackground>,
+    pub background_images: &'a [BackgroundImage],
+    /// Screen and redactions for the blurred-screen virtual background
+    pub screen: Option<(ScreenSource, ScreenTransform)>,
+    pub redactions: &'a [RedactionRegion],
+    pub background_scratch: Option<web_sys::HtmlCanvasElement>,
+    pub redact_scratch: Option<web_sys::HtmlCanvasElement>,
+}
+
+impl Layer for CameraLayer<'_> {
+    fn id(&self) -> LayerId {
+        LayerId::Camera
+    }
+
+    fn z_order(&self) -> i32 {
+        self.z_order
+    }
+
+    fn is_visible(&self) -> bool {
+        self.video.is_some() && self.opacity > 0.0
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        Some(self.rect)
+    }
+
+    fn hit_test(&self, x: f64, y: f64) -> bool {
+        let (left, top, width, height) = self.rect;
+        self.interactive && x >= left && x <= left + width && y >= top && y <= top + height
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, _width: f64, _height: f64) {
+        let Some(camera_video) = &self.video else {
+            return;
+        };
+        let dest = self.rect;
+
+        ctx.save();
+        ctx.set_global_alpha(self.opacity);
+        frame::rounded_rect_path(ctx, dest, self.corner_radius);
+        ctx.clip();
+
+        match self.virtual_background {
+            Some(VirtualBackground::Color(color)) => {
+                ctx.set_fill_style_str(color);
+                ctx.fill_rect(dest.0, dest.1, dest.2, dest.3);
+            }
+            Some(VirtualBackground::Image(index)) => {
+                if let Some(background) = self.background_images.get(*index) {
+                    image::draw_cover(ctx, &background.image, dest.0, dest.1, dest.2, dest.3);
+                }
+            }
+            Some(VirtualBackground::BlurredScreen) => {
+                if let (Some((source, transform)), Some(scratch), Some(redact_scratch)) =
+                    (&self.screen, &self.background_scratch, &self.redact_scratch)
+                {
+                    background::draw_blurred_screen(
+                        ctx,
+                        scratch,
+                        redact_scratch,
+                        source,
+                        transform.src,
+                        self.redactions,
+                        dest,
+                    );
+                }
+            }
+            None => {}
+        }
+
+        self.look.draw(ctx, camera_video, dest);
+        ctx.restore();
+    }
+}
+
+/// The frame shown before the last scene recall, faded or slid away
+pub struct SceneTransitionLayer {
+    pub snapshot: Option<web_sys::HtmlCanvasElement>,
+    pub transition: SceneTransition,
+    pub progress: f64,
+}
+
+impl Layer for SceneTransitionLayer {
+    fn id(&self) -> LayerId {
+        LayerId::SceneTransition
+    }
+
+    fn z_order(&self) -> i32 {
+        z::SCENE_TRANSITION
+    }
+
+    fn is_visible(&self) -> bool {
+        self.snapshot.is_some() && self.progress < 1.0
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        None
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        if let Some(snapshot) = &self.snapshot {
+            self.transition.draw(ctx, snapshot, width, height, self.progress);
+        }
+    }
+}
+
+/// The crop rectangle being dragged, with the rest of the canvas dimmed
+pub struct CropSelectionLayer {
+    pub selection: Option<((f64, f64), (f64, f64))>,
+}
+
+impl Layer for CropSelectionLayer {
+    fn id(&self) -> LayerId {
+        LayerId::CropSelection
+    }
+
+    fn z_order(&self) -> i32 {
+        z::CROP_SELECTION
+    }
+
+    fn is_visible(&self) -> bool {
+        self.selection.is_some()
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.selection.map(|(start, end)| selection_rect(start, end))
+    }
+
+    fn hit_test(&self, _x: f64, _y: f64) -> bool {
+        false
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, viewport_width: f64, viewport_height: f64) {
+        let Some((x, y, width, height)) = self.bounds() else {
+            return;
+        };
+
+        // Draw selection rectangle
+        ctx.set_stroke_style_str("rgba(59, 130, 246, 0.8)");
+        ctx.set_line_width(2.0);
+        ctx.stroke_rect(x, y, width, height);
+
+        // Draw semi-transparent overlay outside selection
+        ctx.set_fill_style_str("rgba(0, 0, 0, 0.3)");
+        // Top
+        ctx.fill_rect(0.0, 0.0, viewport_width, y);
+        // Bottom
+        ctx.fill_rect(0.0, y + height, viewport_width, viewport_height - (y + height));
+        // Left
+        ctx.fill_rect(0.0, y, x, height);
+        // Right
+        ctx.fill_rect(x + width, y, viewport_width - (x + width), height);
+    }
+}
+
+/// The redaction region being dragged
+pub struct RedactSelectionLayer {
+    pub selection: Option<((f64, f64), (f64, f64))>,
+}
+
+impl Layer for RedactSelectionLayer {
+    fn id(&self) -> LayerId {
+        LayerId::RedactSelection
+    }
+
+    fn z_order(&self) -> i32 {
+        z::REDACT_SELECTION
+    }
+
+    fn is_visible(&self) -> bool {
+        self.selection.is_some()
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.selection.map(|(start, end)| selection_rect(start, end))
+    }
+
+    fn hit_test(&self, _x: f64, _y: f64) -> bool {
+        false
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, _width: f64, _height: f64) {
+        let Some((x, y, width, height)) = self.bounds() else {
+            return;
+        };
+
+        ctx.save();
+        ctx.set_stroke_style_str("rgba(239, 68, 68, 0.9)");
+        ctx.set_fill_style_str("rgba(239, 68, 68, 0.2)");
+        ctx.set_line_width(2.0);
+        let _ = ctx.set_line_dash(&js_sys::Array::of2(&8.0.into(), &6.0.into()));
+        ctx.fill_rect(x, y, width, height);
+        ctx.stroke_rect(x, y, width, height);
+        ctx.restore();
+    }
+}
+
+// Size of the large presentation pointer
+const POINTER_SIZE: f64 = 60.0;
+
+/// The large presentation pointer
+pub struct PointerLayer {
+    pub position: Option<(f64, f64)>,
+}
+
+impl Layer for PointerLayer {
+    fn id(&self) -> LayerId {
+        LayerId::Pointer
+    }
+
+    fn z_order(&self) -> i32 {
+        z::POINTER
+    }
+
+    fn is_visible(&self) -> bool {
+        self.position.is_some()
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.position.map(|(x, y)| (x, y, POINTER_SIZE * 0.6, POINTER_SIZE * 0.6))
+    }
+
+    fn hit_test(&self, _x: f64, _y: f64) -> bool {
+        false
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, _width: f64, _height: f64) {
+        let Some(pos) = self.position else {
+            return;
+        };
+
+        // Draw pointer arrow shape
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.9)");
+        ctx.set_stroke_style_str("rgba(0, 0, 0, 0.8)");
+        ctx.set_line_width(2.0);
+
+        ctx.begin_path();
+        // Arrow with acute angle pointing tip
+        ctx.move_to(pos.0, pos.1); // Tip of arrow
+        ctx.line_to(pos.0 + POINTER_SIZE * 0.2, pos.1 + POINTER_SIZE * 0.6); // Left side of shaft
+        ctx.line_to(pos.0 + POINTER_SIZE * 0.3, pos.1 + POINTER_SIZE * 0.3); // Indent for arrow notch
+        ctx.line_to(pos.0 + POINTER_SIZE * 0.6, pos.1 + POINTER_SIZE * 0.2); // Right side of shaft
+        ctx.close_path();
+
+        ctx.fill();
+        ctx.stroke();
+    }
+}
+
+/// A curtain covering the whole composed frame
+pub struct CurtainLayer<'a> {
+    pub curtain: Option<&'a Curtain>,
+    pub opacity: f64,
+}
+
+impl Layer for CurtainLayer<'_> {
+    fn id(&self) -> LayerId {
+        LayerId::Curtain
+    }
+
+    fn z_order(&self) -> i32 {
+        z::CURTAIN
+    }
+
+    fn is_visible(&self) -> bool {
+        self.curtain.is_some() && self.opacity > 0.0
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        None
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        if let Some(curtain) = self.curtain {
+            curtain.draw(ctx, width, height, self.opacity);
+        }
+    }
+}
+
+/// The 3, 2, 1 countdown before recording starts
+pub struct CountdownLayer {
+    pub value: u32,
+}
+
+impl Layer for CountdownLayer {
+    fn id(&self) -> LayerId {
+        LayerId::Countdown
+    }
+
+    fn z_order(&self) -> i32 {
+        z::COUNTDOWN
+    }
+
+    fn is_visible(&self) -> bool {
+        self.value > 0
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        None
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64) {
+        ctx.save();
+
+        // Set up extra large font for countdown
+        ctx.set_font("bold 400px Arial");
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        // White text with black outline
+        ctx.set_fill_style_str("white");
+        ctx.set_stroke_style_str("black");
+        ctx.set_line_width(12.0);
+
+        let text = self.value.to_string();
+        let center_x = width / 2.0;
+        let center_y = height / 2.0;
+
+        // Draw text with outline
+        let _ = ctx.stroke_text(&text, center_x, center_y);
+        let _ = ctx.fill_text(&text, center_x, center_y);
+
+        ctx.restore();
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::RecorderError;
+use crate::{image, storage};
+
+const CURTAINS_KEY: &str = "demrec.curtains";
+
+/// How long a curtain takes to fade in or out
+pub const CURTAIN_FADE_MS: f64 = 400.0;
+
+/// A slate that replaces the whole recorded frame, e.g. "Be right back"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Curtain {
+    pub name: String,
+    /// Key that toggles this curtain (single character, case-insensitive)
+    pub key: String,
+    pub text: String,
+    pub color: String,
+    /// Background image as a data URL
+    pub image: Option<String>,
+    pub mute_mic: bool,
+    #[serde(skip)]
+    pub image_element: Option<web_sys::HtmlImageElement>,
+}
+
+impl Curtain {
+    pub fn new(name: &str, key: &str, text: &str, color: &str, mute_mic: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            key: key.to_string(),
+            text: text.to_string(),
+            color: color.to_string(),
+            image: None,
+            mute_mic,
+            image_element: None,
+        }
+    }
+
+    pub fn set_image(&mut self, url: Option<String>) {
+        self.image_element = url.as_deref().and_then(image::load_image);
+        self.image = url;
+    }
+
+    pub fn matches_key(&self, key: &str) -> bool {
+        !self.key.is_empty() && self.key.eq_ignore_ascii_case(key)
+    }
+
+    /// Draw the curtain over the whole canvas
+    pub fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, width: f64, height: f64, opacity: f64) {
+        ctx.save();
+        ctx.set_global_alpha(opacity);
+
+        ctx.set_fill_style_str(&self.color);
+        ctx.fill_rect(0.0, 0.0, width, height);
+        if let Some(image) = &self.image_element {
+            image::draw_cover(ctx, image, 0.0, 0.0, width, height);
+        }
+
+        if !self.text.is_empty() {
+            ctx.set_font(&format!("bold {}px Arial", (height / 10.0).round()));
+            ctx.set_text_align("center");
+            ctx.set_text_baseline("middle");
+            ctx.set_shadow_color("rgba(0, 0, 0, 0.6)");
+            ctx.set_shadow_blur(16.0);
+            ctx.set_fill_style_str("white");
+            let _ = ctx.fill_text(&self.text, width / 2.0, height / 2.0);
+        }
+
+        ctx.restore();
+    }
+}
+
+pub fn default_curtains() -> Vec<Curtain> {
+    vec![
+        Curtain::new("Be right back", "b", "Be right back", "#1f2937", true),
+        Curtain::new("Starting soon", "s", "Starting soon", "#312e81", false),
+    ]
+}
+
+pub fn load_curtains() -> Vec<Curtain> {
+    match storage::load_json::<Vec<Curtain>>(CURTAINS_KEY) {
+        Some(mut curtains) => {
+            for curtain in &mut curtains {
+                let image = curtain.image.take();
+                curtain.set_image(image);
+            }
+            curtains
+        }
+        None => default_curtains(),
+    }
+}
+
+pub fn save_curtains(curtains: &[Curtain]) -> Result<(), RecorderError> {
+    storage::try_save_json(CURTAINS_KEY, &curtains, "curtains")
+}
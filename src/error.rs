@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::storage;
+
+const DIAGNOSTICS_KEY: &str = "demrec.diagnostics";
+
+// Oldest diagnostics entries are dropped beyond this
+const MAX_DIAGNOSTICS: usize = 200;
+
+/// A capture device the recorder asks the browser for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Device {
+    Camera,
+    Microphone,
+    Screen,
+}
+
+impl Device {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Device::Camera => "camera",
+            Device::Microphone => "microphone",
+            Device::Screen => "screen",
+        }
+    }
+}
+
+/// Something that went wrong, with enough detail to tell the user how to fix it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecorderError {
+    PermissionDenied(Device),
+    /// The screen picker was closed without choosing anything
+    Cancelled(Device),
+    DeviceNotFound(Device),
+    /// Another app or tab holds the device
+    DeviceBusy(Device),
+    /// The browser can't encode any of the recording formats
+    UnsupportedCodec,
+    /// A browser feature the recorder needs is missing
+    Unsupported(String),
+    /// localStorage is full, naming what could not be saved
+    StorageFull(String),
+    /// Browser storage failed for another reason, such as being turned off, with the cause
+    StorageFailed(String),
+    /// A scene or curtain key the recorder uses itself, naming the key
+    HotkeyTaken(String),
+    RecordingFailed(String),
+}
+
+impl RecorderError {
+    /// Error for a failed media request from the DOMException name
+    pub fn from_dom_error_name(device: Device, name: &str) -> Self {
+        match name {
+            // Closing the screen picker is reported the same as a denial
+            "NotAllowedError" | "SecurityError" if device == Device::Screen => RecorderError::Cancelled(device),
+            "NotAllowedError" | "SecurityError" | "PermissionDeniedError" => RecorderError::PermissionDenied(device),
+            "NotFoundError" | "OverconstrainedError" | "DevicesNotFoundError" => RecorderError::DeviceNotFound(device),
+            "NotReadableError" | "AbortError" | "TrackStartError" => RecorderError::DeviceBusy(device),
+            "NotSupportedError" | "TypeError" => {
+                RecorderError::Unsupported(format!("Capturing the {}", device.label()))
+            }
+            _ => RecorderError::RecordingFailed(format!("Could not start the {} ({name})", device.label())),
+        }
+    }
+
+    /// Error for a rejected getUserMedia or getDisplayMedia promise
+    pub fn from_media_error(device: Device, error: &JsValue) -> Self {
+        let name = js_sys::Reflect::get(error, &"name".into())
+            .ok()
+            .and_then(|name| name.as_string())
+            .unwrap_or_default();
+        Self::from_dom_error_name(device, &name)
+    }
+
+    /// Whether this is the user changing their mind rather than a failure
+    pub fn is_cancellation(&self) -> bool {
+        matches!(self, RecorderError::Cancelled(_))
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> String {
+        match self {
+            RecorderError::PermissionDenied(Device::Screen) => {
+                "Allow screen recording for this browser in your system privacy settings, then try again.".to_string()
+            }
+            RecorderError::PermissionDenied(device) => format!(
+                "Click the lock icon in the address bar, allow the {}, and try again.",
+                device.label()
+            ),
+            RecorderError::Cancelled(_) => "Click the screen button again and choose a screen, window or tab.".to_string(),
+            RecorderError::DeviceNotFound(device) => {
+                format!("Connect a {} or check that it is enabled in your system settings.", device.label())
+            }
+            RecorderError::DeviceBusy(device) => format!(
+                "Close other apps or tabs using the {} (video calls, other recorders) and try again.",
+                device.label()
+            ),
+            RecorderError::UnsupportedCodec => "Try a recent version of Chrome, Edge or Firefox.".to_string(),
+            RecorderError::Unsupported(_) => "Try a recent version of Chrome or Edge.".to_string(),
+            RecorderError::StorageFull(_) => {
+                "Delete old recordings, unused scenes, curtains or background images to free up browser storage.".to_string()
+            }
+            RecorderError::StorageFailed(_) => {
+                "Check that the browser allows this site to store data; private windows and strict privacy settings may block it.".to_string()
+            }
+            RecorderError::HotkeyTaken(_) => format!("Pick another key; {} are used by the recorder.", crate::hotkey::reserved_list()),
+            RecorderError::RecordingFailed(_) => "Try recording again. If it keeps failing, reload the page.".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for RecorderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecorderError::PermissionDenied(device) => write!(f, "Access to the {} was denied", device.label()),
+            RecorderError::Cancelled(device) => write!(f, "Sharing the {} was cancelled", device.label()),
+            RecorderError::DeviceNotFound(device) => write!(f, "No {} was found", device.label()),
+            RecorderError::DeviceBusy(device) => write!(f, "The {} is in use by another app", device.label()),
+            RecorderError::UnsupportedCodec => write!(f, "This browser can't encode the recording"),
+            RecorderError::Unsupported(feature) => write!(f, "{feature} isn't supported by this browser"),
+            RecorderError::StorageFull(what) => write!(f, "Could not save {what}, browser storage is full"),
+            RecorderError::StorageFailed(message) => write!(f, "{message}"),
+            RecorderError::HotkeyTaken(key) => write!(f, "{key} is already used by the recorder"),
+            RecorderError::RecordingFailed(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for RecorderError {}
+
+/// An error as kept in the diagnostics log
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticEntry {
+    /// Milliseconds since the epoch
+    pub time: f64,
+    pub error: RecorderError,
+    pub user_agent: String,
+}
+
+pub fn load_diagnostics() -> Vec<DiagnosticEntry> {
+    storage::load_json(DIAGNOSTICS_KEY).unwrap_or_default()
+}
+
+/// Append an error to the diagnostics log kept in localStorage
+pub fn log_diagnostic(error: &RecorderError) {
+    let user_agent = web_sys::window()
+        .and_then(|window| window.navigator().user_agent().ok())
+        .unwrap_or_default();
+
+    let mut entries = load_diagnostics();
+    entries.push(DiagnosticEntry {
+        time: js_sys::Date::now(),
+        error: error.clone(),
+        user_agent,
+    });
+    let excess = entries.len().saturating_sub(MAX_DIAGNOSTICS);
+    entries.drain(..excess);
+
+    // Make room by dropping the older half if storage is full
+    if !storage::save_json(DIAGNOSTICS_KEY, &entries) {
+        entries.drain(..entries.len() / 2);
+        storage::save_json(DIAGNOSTICS_KEY, &entries);
+    }
+}
+
+pub fn clear_diagnostics() {
+    storage::save_json(DIAGNOSTICS_KEY, &Vec::<DiagnosticEntry>::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_dom_error_names() {
+        assert_eq!(
+            RecorderError::from_dom_error_name(Device::Camera, "NotAllowedError"),
+            RecorderError::PermissionDenied(Device::Camera)
+        );
+        assert_eq!(
+            RecorderError::from_dom_error_name(Device::Microphone, "NotFoundError"),
+            RecorderError::DeviceNotFound(Device::Microphone)
+        );
+        assert_eq!(
+            RecorderError::from_dom_error_name(Device::Camera, "NotReadableError"),
+            RecorderError::DeviceBusy(Device::Camera)
+        );
+    }
+
+    #[test]
+    fn closing_the_screen_picker_is_a_cancellation() {
+        let error = RecorderError::from_dom_error_name(Device::Screen, "NotAllowedError");
+        assert_eq!(error, RecorderError::Cancelled(Device::Screen));
+        assert!(error.is_cancellation());
+    }
+
+    #[test]
+    fn unknown_names_keep_the_name() {
+        let error = RecorderError::from_dom_error_name(Device::Camera, "WeirdError");
+        assert!(error.to_string().contains("WeirdError"));
+    }
+
+    #[test]
+    fn survives_a_storage_round_trip() {
+        let error = RecorderError::StorageFull("scenes".into());
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(serde_json::from_str::<RecorderError>(&json).unwrap(), error);
+    }
+}
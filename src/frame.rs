@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const FRAME_STYLE_KEY: &str = "demrec.frame_style";
+
+/// Height of the window title bar drawn above the screen
+pub const CHROME_HEIGHT: f64 = 32.0;
+
+/// How the screen share is framed on the canvas, for a polished product-video look
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameStyle {
+    pub enabled: bool,
+    /// Inset from the edges of the screen area, in canvas pixels
+    pub padding: f64,
+    pub corner_radius: f64,
+    /// Blur radius of the drop shadow, 0 for none
+    pub shadow: f64,
+    /// Draw a window title bar with traffic-light buttons above the screen
+    pub window_chrome: bool,
+}
+
+impl Default for FrameStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            padding: 64.0,
+            corner_radius: 12.0,
+            shadow: 32.0,
+            window_chrome: false,
+        }
+    }
+}
+
+impl FrameStyle {
+    /// Part of the layout's screen area (x, y, width, height) the screen is
+    /// fitted into, leaving room for the padding and title bar
+    pub fn screen_area(&self, area: (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+        let (x, y, width, height) = area;
+        if !self.enabled {
+            return area;
+        }
+
+        let chrome = if self.window_chrome { CHROME_HEIGHT } else { 0.0 };
+        let padding = self.padding.min(width / 4.0).min(height / 4.0);
+        (
+            x + padding,
+            y + padding + chrome,
+            (width - padding * 2.0).max(1.0),
+            (height - padding * 2.0 - chrome).max(1.0),
+        )
+    }
+
+    // Outline of the whole frame around the drawn screen, including the title bar
+    fn outline(&self, screen: (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+        let (x, y, width, height) = screen;
+        if self.window_chrome {
+            (x, y - CHROME_HEIGHT, width, height + CHROME_HEIGHT)
+        } else {
+            screen
+        }
+    }
+
+    /// Draw the shadow and title bar behind the screen drawn at `screen`
+    pub fn draw_back(&self, ctx: &web_sys::CanvasRenderingContext2d, screen: (f64, f64, f64, f64)) {
+        let outline = self.outline(screen);
+
+        ctx.save();
+        if self.shadow > 0.0 {
+            ctx.set_shadow_color("rgba(0, 0, 0, 0.5)");
+            ctx.set_shadow_blur(self.shadow);
+            ctx.set_shadow_offset_y(self.shadow / 3.0);
+        }
+        ctx.set_fill_style_str(if self.window_chrome { "#e5e7eb" } else { "black" });
+        rounded_rect_path(ctx, outline, self.corner_radius);
+        ctx.fill();
+        ctx.restore();
+
+        if self.window_chrome {
+            // Traffic-light buttons
+            let center_y = outline.1 + CHROME_HEIGHT / 2.0;
+            for (index, color) in ["#ff5f57", "#febc2e", "#28c840"].into_iter().enumerate() {
+                ctx.set_fill_style_str(color);
+                ctx.begin_path();
+                let _ = ctx.arc(outline.0 + 18.0 + index as f64 * 20.0, center_y, 6.0, 0.0, std::f64::consts::TAU);
+                ctx.fill();
+            }
+        }
+    }
+
+    /// Clip the context to the screen drawn at `screen`, rounding the corners
+    /// not covered by the title bar
+    pub fn clip_screen(&self, ctx: &web_sys::CanvasRenderingContext2d, screen: (f64, f64, f64, f64)) {
+        if self.window_chrome {
+            // Round only the bottom corners by clipping to the whole outline
+            rounded_rect_path(ctx, self.outline(screen), self.corner_radius);
+            ctx.clip();
+            ctx.begin_path();
+            ctx.rect(screen.0, screen.1, screen.2, screen.3);
+        } else {
+            rounded_rect_path(ctx, screen, self.corner_radius);
+        }
+        ctx.clip();
+    }
+}
+
+/// Start a new path outlining a rounded rectangle
+pub fn rounded_rect_path(ctx: &web_sys::CanvasRenderingContext2d, rect: (f64, f64, f64, f64), radius: f64) {
+    let (x, y, width, height) = rect;
+    let radius = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+
+    ctx.begin_path();
+    ctx.move_to(x + radius, y);
+    let _ = ctx.arc_to(x + width, y, x + width, y + height, radius);
+    let _ = ctx.arc_to(x + width, y + height, x, y + height, radius);
+    let _ = ctx.arc_to(x, y + height, x, y, radius);
+    let _ = ctx.arc_to(x, y, x + width, y, radius);
+    ctx.close_path();
+}
+
+pub fn load_frame_style() -> FrameStyle {
+    storage::load_json(FRAME_STYLE_KEY).unwrap_or_default()
+}
+
+pub fn save_frame_style(style: &FrameStyle) {
+    storage::save_json(FRAME_STYLE_KEY, style);
+}
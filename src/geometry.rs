@@ -0,0 +1,393 @@
+//! Pure coordinate math shared by drawing and mouse handling. Rectangles are
+//! (x, y, width, height) tuples. Three coordinate spaces are involved:
+//!
+//! - viewport: CSS pixels of the page, where mouse events happen
+//! - output: pixels of the recorded canvas
+//! - source: pixels of a video (screen share or camera)
+
+/// Normalize a dragged selection between two points to a rectangle
+pub fn selection_rect(start: (f64, f64), end: (f64, f64)) -> (f64, f64, f64, f64) {
+    (
+        start.0.min(end.0),
+        start.1.min(end.1),
+        (start.0 - end.0).abs(),
+        (start.1 - end.1).abs(),
+    )
+}
+
+/// Intersection of two rectangles, None if they don't overlap
+pub fn intersect(a: (f64, f64, f64, f64), b: (f64, f64, f64, f64)) -> Option<(f64, f64, f64, f64)> {
+    let left = a.0.max(b.0);
+    let top = a.1.max(b.1);
+    let right = (a.0 + a.2).min(b.0 + b.2);
+    let bottom = (a.1 + a.3).min(b.1 + b.3);
+
+    if right > left && bottom > top {
+        Some((left, top, right - left, bottom - top))
+    } else {
+        None
+    }
+}
+
+/// Maps between the page viewport and the recorded output canvas, which is
+/// stretched to fill the viewport
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewportMapping {
+    pub viewport: (f64, f64),
+    pub output: (f64, f64),
+}
+
+impl ViewportMapping {
+    // Output pixels per viewport pixel, 1 if either size is degenerate
+    fn scale(self) -> (f64, f64) {
+        let (viewport_width, viewport_height) = self.viewport;
+        let (output_width, output_height) = self.output;
+        if viewport_width > 0.0 && viewport_height > 0.0 && output_width > 0.0 && output_height > 0.0 {
+            (output_width / viewport_width, output_height / viewport_height)
+        } else {
+            (1.0, 1.0)
+        }
+    }
+
+    pub fn to_output(self, viewport_x: f64, viewport_y: f64) -> (f64, f64) {
+        let (scale_x, scale_y) = self.scale();
+        (viewport_x * scale_x, viewport_y * scale_y)
+    }
+
+    pub fn to_viewport(self, output_x: f64, output_y: f64) -> (f64, f64) {
+        let (scale_x, scale_y) = self.scale();
+        (output_x / scale_x, output_y / scale_y)
+    }
+}
+
+/// Maps between screen video coordinates and canvas coordinates for the
+/// letterboxed (and optionally cropped) screen share.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenTransform {
+    /// Source rectangle in screen video coordinates (x, y, width, height)
+    pub src: (f64, f64, f64, f64),
+    /// Top-left corner of the drawn screen on the canvas
+    pub dest_x: f64,
+    pub dest_y: f64,
+    /// Canvas pixels per video pixel
+    pub scale: f64,
+}
+
+// Whether a size can be drawn into or from
+fn is_drawable(width: f64, height: f64) -> bool {
+    width.is_finite() && height.is_finite() && width > 0.0 && height > 0.0
+}
+
+impl ScreenTransform {
+    /// Fit the source rectangle into an area of the canvas (x, y, width, height),
+    /// centered, keeping aspect ratio. None if either has no size.
+    pub fn fit(src: (f64, f64, f64, f64), area: (f64, f64, f64, f64)) -> Option<Self> {
+        let (area_x, area_y, area_width, area_height) = area;
+        if !is_drawable(src.2, src.3) || !is_drawable(area_width, area_height) {
+            return None;
+        }
+
+        let scale_x = area_width / src.2;
+        let scale_y = area_height / src.3;
+        let scale = scale_x.min(scale_y); // Use min to fit (letterbox if needed)
+
+        Some(Self {
+            src,
+            dest_x: area_x + (area_width - src.2 * scale) / 2.0,
+            dest_y: area_y + (area_height - src.3 * scale) / 2.0,
+            scale,
+        })
+    }
+
+    /// Scale the source rectangle to cover the whole viewport, centered,
+    /// cropping whatever overflows. None if either has no size.
+    pub fn cover(src: (f64, f64, f64, f64), viewport_width: f64, viewport_height: f64) -> Option<Self> {
+        if !is_drawable(src.2, src.3) || !is_drawable(viewport_width, viewport_height) {
+            return None;
+        }
+
+        let scale = (viewport_width / src.2).max(viewport_height / src.3);
+
+        Some(Self {
+            src,
+            dest_x: (viewport_width - src.2 * scale) / 2.0,
+            dest_y: (viewport_height - src.3 * scale) / 2.0,
+            scale,
+        })
+    }
+
+    /// Size of the drawn screen on the canvas
+    pub fn dest_size(self) -> (f64, f64) {
+        (self.src.2 * self.scale, self.src.3 * self.scale)
+    }
+
+    /// Rectangle of the drawn screen on the canvas (x, y, width, height)
+    pub fn dest_rect(self) -> (f64, f64, f64, f64) {
+        let (width, height) = self.dest_size();
+        (self.dest_x, self.dest_y, width, height)
+    }
+
+    pub fn to_canvas(self, video_x: f64, video_y: f64) -> (f64, f64) {
+        (
+            self.dest_x + (video_x - self.src.0) * self.scale,
+            self.dest_y + (video_y - self.src.1) * self.scale,
+        )
+    }
+
+    pub fn to_video(self, canvas_x: f64, canvas_y: f64) -> (f64, f64) {
+        (
+            self.src.0 + (canvas_x - self.dest_x) / self.scale,
+            self.src.1 + (canvas_y - self.dest_y) / self.scale,
+        )
+    }
+
+    /// Convert a canvas rectangle to video coordinates, clipped to the video.
+    /// None if nothing of it lies on the video.
+    pub fn rect_to_video(self, rect: (f64, f64, f64, f64), video_size: (f64, f64)) -> Option<(f64, f64, f64, f64)> {
+        let (left, top) = self.to_video(rect.0, rect.1);
+        let (right, bottom) = self.to_video(rect.0 + rect.2, rect.1 + rect.3);
+        intersect(
+            (left, top, right - left, bottom - top),
+            (0.0, 0.0, video_size.0, video_size.1),
+        )
+    }
+}
+
+/// Part of the camera video shown in a camera area: zoomed in around the
+/// center, then cropped to the area's aspect ratio (width / height). Returns
+/// (x, y, width, height) in camera video coordinates. Zoom below 1 is treated
+/// as 1 and a degenerate aspect keeps the video's own.
+pub fn camera_source_rect(video_width: f64, video_height: f64, zoom: f64, aspect: f64) -> (f64, f64, f64, f64) {
+    if !is_drawable(video_width, video_height) {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+    let zoom = if zoom.is_finite() { zoom.max(1.0) } else { 1.0 };
+
+    // First apply zoom crop
+    let zoomed_width = video_width / zoom;
+    let zoomed_height = video_height / zoom;
+
+    // Calculate final crop dimensions to match camera aspect ratio
+    let video_aspect = zoomed_width / zoomed_height;
+    let aspect = if aspect.is_finite() && aspect > 0.0 { aspect } else { video_aspect };
+    let (final_width, final_height) = if video_aspect > aspect {
+        // Video is wider, crop horizontally
+        (zoomed_height * aspect, zoomed_height)
+    } else {
+        // Video is taller, crop vertically
+        (zoomed_width, zoomed_width / aspect)
+    };
+
+    // Center the final crop area within the zoomed area
+    let base_x = (video_width - zoomed_width) / 2.0;
+    let base_y = (video_height - zoomed_height) / 2.0;
+    (
+        base_x + (zoomed_width - final_width) / 2.0,
+        base_y + (zoomed_height - final_height) / 2.0,
+        final_width,
+        final_height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        close_at(a, b, 1.0)
+    }
+
+    // Equal up to rounding, relative to the larger of the values and `magnitude`
+    fn close_at(a: f64, b: f64, magnitude: f64) -> bool {
+        (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(magnitude)
+    }
+
+    #[test]
+    fn fit_letterboxes_wide_source() {
+        let transform = ScreenTransform::fit((0.0, 0.0, 1920.0, 1080.0), (0.0, 0.0, 1000.0, 1000.0)).unwrap();
+        assert!(close(transform.scale, 1000.0 / 1920.0));
+        assert!(close(transform.dest_x, 0.0));
+        assert!(close(transform.dest_y, (1000.0 - 1080.0 * transform.scale) / 2.0));
+    }
+
+    #[test]
+    fn fit_offsets_into_area() {
+        let transform = ScreenTransform::fit((0.0, 0.0, 100.0, 100.0), (50.0, 20.0, 200.0, 100.0)).unwrap();
+        assert_eq!(transform.dest_rect(), (100.0, 20.0, 100.0, 100.0));
+    }
+
+    #[test]
+    fn fit_rejects_zero_sizes() {
+        assert_eq!(ScreenTransform::fit((0.0, 0.0, 0.0, 1080.0), (0.0, 0.0, 100.0, 100.0)), None);
+        assert_eq!(ScreenTransform::fit((0.0, 0.0, 1920.0, 1080.0), (0.0, 0.0, 100.0, 0.0)), None);
+        assert_eq!(ScreenTransform::cover((0.0, 0.0, 0.0, 0.0), 100.0, 100.0), None);
+        assert_eq!(ScreenTransform::fit((0.0, 0.0, f64::NAN, 10.0), (0.0, 0.0, 100.0, 100.0)), None);
+    }
+
+    #[test]
+    fn cover_crops_overflow() {
+        let transform = ScreenTransform::cover((0.0, 0.0, 200.0, 100.0), 100.0, 100.0).unwrap();
+        assert_eq!(transform.dest_rect(), (-50.0, 0.0, 200.0, 100.0));
+    }
+
+    #[test]
+    fn cropped_source_maps_to_video() {
+        // A crop of the right half of the video fills the canvas
+        let transform = ScreenTransform::fit((960.0, 0.0, 960.0, 1080.0), (0.0, 0.0, 960.0, 1080.0)).unwrap();
+        assert_eq!(transform.to_video(0.0, 0.0), (960.0, 0.0));
+        assert_eq!(transform.to_canvas(1920.0, 1080.0), (960.0, 1080.0));
+    }
+
+    #[test]
+    fn rect_to_video_clips_to_video() {
+        let transform = ScreenTransform::fit((0.0, 0.0, 100.0, 100.0), (0.0, 0.0, 200.0, 100.0)).unwrap();
+        // The video is drawn at x 50..150, a selection from 0 to 100 covers its left half
+        assert_eq!(
+            transform.rect_to_video((0.0, 0.0, 100.0, 100.0), (100.0, 100.0)),
+            Some((0.0, 0.0, 50.0, 100.0))
+        );
+        // Entirely in the letterbox bar
+        assert_eq!(transform.rect_to_video((0.0, 0.0, 40.0, 100.0), (100.0, 100.0)), None);
+    }
+
+    #[test]
+    fn selection_rect_normalizes_direction() {
+        assert_eq!(selection_rect((10.0, 40.0), (0.0, 20.0)), (0.0, 20.0, 10.0, 20.0));
+    }
+
+    #[test]
+    fn intersect_of_disjoint_rects_is_none() {
+        assert_eq!(intersect((0.0, 0.0, 10.0, 10.0), (10.0, 0.0, 10.0, 10.0)), None);
+        assert_eq!(intersect((0.0, 0.0, 10.0, 10.0), (5.0, 5.0, 10.0, 10.0)), Some((5.0, 5.0, 5.0, 5.0)));
+    }
+
+    #[test]
+    fn camera_source_rect_zooms_and_crops() {
+        // 2x zoom of a 16:9 video into a square bubble
+        let (x, y, width, height) = camera_source_rect(1280.0, 720.0, 2.0, 1.0);
+        assert_eq!((width, height), (360.0, 360.0));
+        assert_eq!((x, y), (460.0, 180.0));
+    }
+
+    #[test]
+    fn camera_source_rect_handles_degenerate_input() {
+        assert_eq!(camera_source_rect(0.0, 720.0, 1.0, 1.0), (0.0, 0.0, 0.0, 0.0));
+        assert_eq!(camera_source_rect(1280.0, 720.0, 0.0, f64::NAN), (0.0, 0.0, 1280.0, 720.0));
+    }
+
+    #[test]
+    fn viewport_mapping_scales_and_ignores_degenerate_sizes() {
+        let mapping = ViewportMapping { viewport: (960.0, 540.0), output: (1920.0, 1080.0) };
+        assert_eq!(mapping.to_output(480.0, 270.0), (960.0, 540.0));
+        let empty = ViewportMapping { viewport: (0.0, 0.0), output: (1920.0, 1080.0) };
+        assert_eq!(empty.to_output(10.0, 20.0), (10.0, 20.0));
+    }
+
+    // Sizes from a sliver to a huge display, so aspect ratios get extreme
+    fn size() -> impl Strategy<Value = f64> {
+        prop_oneof![0.01f64..1.0, 1.0f64..10_000.0, 10_000.0f64..1e6]
+    }
+
+    proptest! {
+        #[test]
+        fn fit_stays_inside_area_and_keeps_aspect(
+            src_width in size(), src_height in size(),
+            area_x in -1000.0f64..1000.0, area_y in -1000.0f64..1000.0,
+            area_width in size(), area_height in size(),
+        ) {
+            let area = (area_x, area_y, area_width, area_height);
+            let transform = ScreenTransform::fit((0.0, 0.0, src_width, src_height), area).unwrap();
+            let (x, y, width, height) = transform.dest_rect();
+
+            prop_assert!(x >= area_x - 1e-6 * area_width.max(1.0));
+            prop_assert!(y >= area_y - 1e-6 * area_height.max(1.0));
+            prop_assert!(x + width <= area_x + area_width + 1e-6 * area_width.max(1.0));
+            prop_assert!(y + height <= area_y + area_height + 1e-6 * area_height.max(1.0));
+            // One side touches the area
+            prop_assert!(close(width, area_width) || close(height, area_height));
+            prop_assert!(close(width / height, src_width / src_height));
+        }
+
+        #[test]
+        fn cover_fills_viewport(
+            src_width in size(), src_height in size(),
+            viewport_width in size(), viewport_height in size(),
+        ) {
+            let transform = ScreenTransform::cover((0.0, 0.0, src_width, src_height), viewport_width, viewport_height).unwrap();
+            let (x, y, width, height) = transform.dest_rect();
+
+            prop_assert!(x <= 1e-6 * viewport_width.max(1.0));
+            prop_assert!(y <= 1e-6 * viewport_height.max(1.0));
+            prop_assert!(x + width >= viewport_width * (1.0 - 1e-9));
+            prop_assert!(y + height >= viewport_height * (1.0 - 1e-9));
+        }
+
+        #[test]
+        fn canvas_video_round_trip(
+            src_x in 0.0f64..1000.0, src_y in 0.0f64..1000.0,
+            src_width in size(), src_height in size(),
+            area_width in size(), area_height in size(),
+            u in 0.0f64..1.0, v in 0.0f64..1.0,
+        ) {
+            let src = (src_x, src_y, src_width, src_height);
+            let transform = ScreenTransform::fit(src, (0.0, 0.0, area_width, area_height)).unwrap();
+            let video = (src_x + u * src_width, src_y + v * src_height);
+            let (canvas_x, canvas_y) = transform.to_canvas(video.0, video.1);
+            let (back_x, back_y) = transform.to_video(canvas_x, canvas_y);
+
+            // Rounding in canvas space grows by 1 / scale on the way back
+            let magnitude = canvas_x.abs().max(canvas_y.abs()).max(1.0) / transform.scale;
+            prop_assert!(close_at(back_x, video.0, magnitude), "{} != {}", back_x, video.0);
+            prop_assert!(close_at(back_y, video.1, magnitude), "{} != {}", back_y, video.1);
+        }
+
+        #[test]
+        fn viewport_output_round_trip(
+            viewport_width in size(), viewport_height in size(),
+            output_width in size(), output_height in size(),
+            x in -1e4f64..1e4, y in -1e4f64..1e4,
+        ) {
+            let mapping = ViewportMapping {
+                viewport: (viewport_width, viewport_height),
+                output: (output_width, output_height),
+            };
+            let (output_x, output_y) = mapping.to_output(x, y);
+            let (back_x, back_y) = mapping.to_viewport(output_x, output_y);
+
+            prop_assert!(close(back_x, x));
+            prop_assert!(close(back_y, y));
+        }
+
+        #[test]
+        fn rect_to_video_stays_on_video(
+            video_width in size(), video_height in size(),
+            area_width in size(), area_height in size(),
+            x in -1e4f64..1e4, y in -1e4f64..1e4, width in 0.0f64..1e4, height in 0.0f64..1e4,
+        ) {
+            let transform = ScreenTransform::fit((0.0, 0.0, video_width, video_height), (0.0, 0.0, area_width, area_height)).unwrap();
+            if let Some((left, top, rect_width, rect_height)) = transform.rect_to_video((x, y, width, height), (video_width, video_height)) {
+                prop_assert!(left >= 0.0 && top >= 0.0);
+                prop_assert!(rect_width > 0.0 && rect_height > 0.0);
+                prop_assert!(left + rect_width <= video_width * (1.0 + 1e-9));
+                prop_assert!(top + rect_height <= video_height * (1.0 + 1e-9));
+            }
+        }
+
+        #[test]
+        fn camera_source_rect_stays_inside_video_with_aspect(
+            video_width in size(), video_height in size(),
+            zoom in 1.0f64..3.0, aspect in 0.01f64..100.0,
+        ) {
+            let (x, y, width, height) = camera_source_rect(video_width, video_height, zoom, aspect);
+
+            prop_assert!(x >= -1e-9 * video_width && y >= -1e-9 * video_height);
+            prop_assert!(x + width <= video_width * (1.0 + 1e-9));
+            prop_assert!(y + height <= video_height * (1.0 + 1e-9));
+            prop_assert!(close(width / height, aspect));
+            // Centered in the video
+            prop_assert!(close(x + width / 2.0, video_width / 2.0));
+            prop_assert!(close(y + height / 2.0, video_height / 2.0));
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Keys the recorder handles itself, which scenes and curtains can't be bound to.
+
+use crate::error::RecorderError;
+use crate::layout::Layout;
+use crate::markers::MARKER_HOTKEY;
+
+/// Freezes the screen share on its current frame, or resumes it
+pub const FREEZE_HOTKEY: &str = "f";
+
+/// Hides the camera behind its privacy style, or shows it again
+pub const PRIVACY_HOTKEY: &str = "c";
+
+/// Built-in keys with what they do
+pub fn reserved() -> Vec<(&'static str, &'static str)> {
+    let mut keys = vec![
+        (FREEZE_HOTKEY, "freeze screen"),
+        (PRIVACY_HOTKEY, "camera privacy"),
+        (MARKER_HOTKEY, "drop marker"),
+    ];
+    keys.extend(Layout::ALL.iter().map(|layout| (layout.hotkey(), layout.label())));
+    keys
+}
+
+/// The reserved keys for a hint, e.g. "F, C, M, 1, 2"
+pub fn reserved_list() -> String {
+    reserved().iter().map(|(key, _)| key.to_uppercase()).collect::<Vec<_>>().join(", ")
+}
+
+/// Check a key a scene or curtain is about to be bound to; empty is no key
+pub fn check_binding(key: &str) -> Result<(), RecorderError> {
+    match reserved().into_iter().find(|(reserved, _)| reserved.eq_ignore_ascii_case(key)) {
+        Some((reserved, action)) => Err(RecorderError::HotkeyTaken(format!("{} ({action})", reserved.to_uppercase()))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_keys_are_taken() {
+        for key in ["f", "F", "c", "m", "M", "1", "5"] {
+            assert!(check_binding(key).is_err(), "{key}");
+        }
+        assert_eq!(
+            check_binding("c").unwrap_err().to_string(),
+            "C (camera privacy) is already used by the recorder"
+        );
+    }
+
+    #[test]
+    fn other_keys_are_free() {
+        for key in ["", "b", "s", "x", "9"] {
+            assert_eq!(check_binding(key), Ok(()), "{key}");
+        }
+        assert_eq!(reserved_list(), "F, C, M, 1, 2, 3, 4, 5");
+    }
+}
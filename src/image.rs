@@ -0,0 +1,126 @@
+use wasm_bindgen::JsCast;
+
+/// Create an image element loading from a URL (object URL, data URL or remote)
+pub fn load_image(url: &str) -> Option<web_sys::HtmlImageElement> {
+    let image = web_sys::HtmlImageElement::new().ok()?;
+    image.set_src(url);
+    Some(image)
+}
+
+/// Encode an image file as a data URL so it can be kept in browser storage
+pub fn data_url(file_name: &str, bytes: &[u8]) -> Option<String> {
+    let extension = file_name.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "image/png",
+    };
+
+    // btoa expects a "binary string" with one character per byte
+    let binary: String = bytes.iter().map(|&byte| byte as char).collect();
+    let encoded = web_sys::window()?.btoa(&binary).ok()?;
+    Some(format!("data:{};base64,{}", mime, encoded))
+}
+
+/// Re-encode an image data URL as a JPEG no larger than `max_size` on either
+/// side, to keep it small enough for localStorage
+pub async fn shrink_data_url(url: &str, max_size: f64) -> Option<String> {
+    let image = load_image(url)?;
+    wasm_bindgen_futures::JsFuture::from(image.decode()).await.ok()?;
+
+    let width = image.natural_width() as f64;
+    let height = image.natural_height() as f64;
+    let scale = (max_size / width.max(height)).min(1.0);
+
+    let canvas = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width((width * scale).round() as u32);
+    canvas.set_height((height * scale).round() as u32);
+    let ctx = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()?;
+    ctx.draw_image_with_html_image_element_and_dw_and_dh(&image, 0.0, 0.0, width * scale, height * scale)
+        .ok()?;
+
+    canvas
+        .to_data_url_with_type_and_encoder_options("image/jpeg", &0.85.into())
+        .ok()
+}
+
+/// JPEG data URL of what a canvas shows, no larger than `max_size` on either side
+pub fn canvas_thumbnail(source: &web_sys::HtmlCanvasElement, max_size: f64) -> Option<String> {
+    let width = source.width() as f64;
+    let height = source.height() as f64;
+    if width == 0.0 || height == 0.0 {
+        return None;
+    }
+    let scale = (max_size / width.max(height)).min(1.0);
+
+    let canvas = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width((width * scale).round() as u32);
+    canvas.set_height((height * scale).round() as u32);
+    let ctx = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()?;
+    ctx.draw_image_with_html_canvas_element_and_dw_and_dh(source, 0.0, 0.0, width * scale, height * scale)
+        .ok()?;
+
+    canvas
+        .to_data_url_with_type_and_encoder_options("image/jpeg", &0.8.into())
+        .ok()
+}
+
+/// Read the first file of a file input event as an image data URL
+pub async fn read_data_url(event: &dioxus::prelude::FormEvent) -> Option<String> {
+    let files = event.files()?;
+    let file_name = files.files().into_iter().next()?;
+    let bytes = files.read_file(&file_name).await?;
+    data_url(&file_name, &bytes)
+}
+
+/// Draw an image scaled to cover the rectangle, cropping whatever overflows
+pub fn draw_cover(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    image: &web_sys::HtmlImageElement,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) {
+    let image_width = image.natural_width() as f64;
+    let image_height = image.natural_height() as f64;
+    if !image.complete() || image_width <= 0.0 || image_height <= 0.0 {
+        return;
+    }
+
+    let scale = (width / image_width).max(height / image_height);
+    let src_width = width / scale;
+    let src_height = height / scale;
+    let _ = ctx.draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+        image,
+        (image_width - src_width) / 2.0,
+        (image_height - src_height) / 2.0,
+        src_width,
+        src_height,
+        x,
+        y,
+        width,
+        height,
+    );
+}
+
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+/// How long switching between layouts takes
+pub const LAYOUT_TRANSITION_MS: f64 = 500.0;
+
+// Corner radius of the rounded camera bubble
+const BUBBLE_RADIUS: f64 = 12.0;
+
+/// Outline of the camera bubble
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraShape {
+    #[default]
+    Rounded,
+    /// A circle, or a pill when the bubble isn't square
+    Circle,
+    Square,
+}
+
+impl CameraShape {
+    pub const ALL: [CameraShape; 3] = [CameraShape::Rounded, CameraShape::Circle, CameraShape::Square];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraShape::Rounded => "Rounded",
+            CameraShape::Circle => "Circle",
+            CameraShape::Square => "Square",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.label() == label)
+    }
+
+    // Corner radius for a bubble of the given rectangle
+    fn radius(&self, bubble: (f64, f64, f64, f64)) -> f64 {
+        match self {
+            CameraShape::Rounded => BUBBLE_RADIUS,
+            CameraShape::Circle => bubble.2.min(bubble.3) / 2.0,
+            CameraShape::Square => 0.0,
+        }
+    }
+}
+
+/// How the screen share and the camera are arranged on the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Layout {
+    /// Full-frame screen with the draggable camera bubble over it
+    Bubble,
+    ScreenOnly,
+    /// Screen on the left half, camera on the right half
+    Split,
+    /// Full-frame camera with the screen inset in the corner
+    CameraLarge,
+    /// Full-frame presenter over the virtual background
+    CameraOnly,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 5] = [
+        Layout::Bubble,
+        Layout::ScreenOnly,
+        Layout::Split,
+        Layout::CameraLarge,
+        Layout::CameraOnly,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Layout::Bubble => "Screen + bubble",
+            Layout::ScreenOnly => "Screen only",
+            Layout::Split => "Side by side",
+            Layout::CameraLarge => "Camera + screen inset",
+            Layout::CameraOnly => "Camera only",
+        }
+    }
+
+    /// Key that switches to this layout
+    pub fn hotkey(&self) -> &'static str {
+        match self {
+            Layout::Bubble => "1",
+            Layout::ScreenOnly => "2",
+            Layout::Split => "3",
+            Layout::CameraLarge => "4",
+            Layout::CameraOnly => "5",
+        }
+    }
+
+    pub fn from_hotkey(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|layout| layout.hotkey() == key)
+    }
+
+    /// Whether the camera fills the frame with the screen drawn over it
+    pub fn camera_behind(&self) -> bool {
+        matches!(self, Layout::CameraLarge | Layout::CameraOnly)
+    }
+
+    /// Where the screen and camera go for a canvas size and camera bubble rectangle
+    pub fn placement(
+        &self,
+        viewport_width: f64,
+        viewport_height: f64,
+        bubble: (f64, f64, f64, f64),
+        shape: CameraShape,
+    ) -> Placement {
+        let full = (0.0, 0.0, viewport_width, viewport_height);
+        let inset = (
+            viewport_width * 0.58,
+            viewport_height * 0.58,
+            viewport_width * 0.38,
+            viewport_height * 0.38,
+        );
+
+        match self {
+            Layout::Bubble => Placement {
+                screen: full,
+                screen_opacity: 1.0,
+                camera: bubble,
+                camera_opacity: 1.0,
+                camera_radius: shape.radius(bubble),
+            },
+            Layout::ScreenOnly => Placement {
+                screen: full,
+                screen_opacity: 1.0,
+                camera: bubble,
+                camera_opacity: 0.0,
+                camera_radius: shape.radius(bubble),
+            },
+            Layout::Split => Placement {
+                screen: (0.0, 0.0, viewport_width / 2.0, viewport_height),
+                screen_opacity: 1.0,
+                camera: (viewport_width / 2.0, 0.0, viewport_width / 2.0, viewport_height),
+                camera_opacity: 1.0,
+                camera_radius: 0.0,
+            },
+            Layout::CameraLarge => Placement {
+                screen: inset,
+                screen_opacity: 1.0,
+                camera: full,
+                camera_opacity: 1.0,
+                camera_radius: 0.0,
+            },
+            Layout::CameraOnly => Placement {
+                screen: inset,
+                screen_opacity: 0.0,
+                camera: full,
+                camera_opacity: 1.0,
+                camera_radius: 0.0,
+            },
+        }
+    }
+}
+
+/// Where the screen and camera are drawn on the canvas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    /// Area the screen is fitted into (x, y, width, height)
+    pub screen: (f64, f64, f64, f64),
+    pub screen_opacity: f64,
+    /// Area the camera covers (x, y, width, height)
+    pub camera: (f64, f64, f64, f64),
+    pub camera_opacity: f64,
+    pub camera_radius: f64,
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+fn lerp_rect(from: (f64, f64, f64, f64), to: (f64, f64, f64, f64), t: f64) -> (f64, f64, f64, f64) {
+    (
+        lerp(from.0, to.0, t),
+        lerp(from.1, to.1, t),
+        lerp(from.2, to.2, t),
+        lerp(from.3, to.3, t),
+    )
+}
+
+impl Placement {
+    /// Placement part way to another one, eased in and out (`progress` 0-1)
+    pub fn towards(&self, to: &Placement, progress: f64) -> Placement {
+        let t = progress.clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+
+        Placement {
+            screen: lerp_rect(self.screen, to.screen, t),
+            screen_opacity: lerp(self.screen_opacity, to.screen_opacity, t),
+            camera: lerp_rect(self.camera, to.camera, t),
+            camera_opacity: lerp(self.camera_opacity, to.camera_opacity, t),
+            camera_radius: lerp(self.camera_radius, to.camera_radius, t),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Screen and camera demo recorder as a Dioxus component.
+//!
+//! Embed [`Recorder`] in an app to get the full recording UI. The demrec binary
+//! is a thin wrapper that renders it full-page.
+
+mod adjust;
+mod animation;
+mod background;
+mod camera;
+pub mod capability;
+mod chroma;
+mod compositor;
+mod curtain;
+mod error;
+mod frame;
+pub mod geometry;
+mod hotkey;
+mod image;
+mod layout;
+mod library;
+mod markers;
+mod media;
+mod opfs;
+mod panels;
+mod preset;
+mod privacy;
+mod recorder;
+mod recording;
+mod redact;
+mod review;
+mod scene;
+mod screen;
+mod session;
+mod storage;
+pub mod webm;
+
+pub use error::{Device, RecorderError};
+pub use recorder::Recorder;
@@ -0,0 +1,314 @@
+//! Recordings kept in the browser: the files live in the origin private file
+//! system under `recordings/`, their names, thumbnails and lengths in a list in
+//! localStorage.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::capability;
+use crate::error::RecorderError;
+use crate::markers::{self, Marker};
+use crate::{opfs, storage, webm};
+
+const RECORDINGS_KEY: &str = "demrec.recordings";
+const RECORDINGS_DIR: &str = "recordings";
+
+/// Longest side of a recording's thumbnail
+pub const THUMBNAIL_SIZE: f64 = 160.0;
+
+/// A recording in the library
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedRecording {
+    pub id: String,
+    pub name: String,
+    pub mime_type: String,
+    /// Milliseconds since the epoch
+    pub created_at: f64,
+    pub duration_ms: f64,
+    /// Bytes
+    pub size: f64,
+    /// Small JPEG data URL
+    pub thumbnail: Option<String>,
+    /// Dropped while recording, also written into WebM files as chapters
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+}
+
+impl SavedRecording {
+    // Name of the file in the origin private file system
+    fn stored_name(&self) -> String {
+        format!("{}.{}", self.id, capability::file_extension(&self.mime_type))
+    }
+
+    /// Name to download the recording as
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.name, capability::file_extension(&self.mime_type))
+    }
+}
+
+/// Name for a new recording, after the time it was made
+pub fn default_name(created_at: f64) -> String {
+    let date = js_sys::Date::new(&created_at.into());
+    format!(
+        "demo {}-{:02}-{:02} {:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+/// A name typed by the user, made safe to use as a file name
+pub fn clean_name(name: &str) -> Option<String> {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '-' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+/// Length as shown in the library, "1:05" or "1:02:03"
+pub fn format_duration(duration_ms: f64) -> String {
+    let seconds = (duration_ms / 1000.0).round().max(0.0) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Size as shown in the library, "820 KB" or "12.5 MB"
+pub fn format_size(bytes: f64) -> String {
+    match bytes {
+        b if b >= 1e9 => format!("{:.1} GB", b / 1e9),
+        b if b >= 1e6 => format!("{:.1} MB", b / 1e6),
+        b => format!("{:.0} KB", b / 1e3),
+    }
+}
+
+pub fn load_recordings() -> Vec<SavedRecording> {
+    storage::load_json(RECORDINGS_KEY).unwrap_or_default()
+}
+
+fn save_recordings(recordings: &[SavedRecording]) -> Result<(), RecorderError> {
+    storage::try_save_json(RECORDINGS_KEY, &recordings, "the recordings list")
+}
+
+/// Give a WebM recording the duration MediaRecorder leaves out, so players can
+/// seek it, returning it with its length in milliseconds where known
+pub async fn make_seekable(blob: web_sys::Blob, mime_type: &str) -> (web_sys::Blob, Option<f64>) {
+    if capability::file_extension(mime_type) != "webm" {
+        return (blob, None);
+    }
+    let Ok(buffer) = wasm_bindgen_futures::JsFuture::from(blob.array_buffer()).await else {
+        return (blob, None);
+    };
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    let fixed = match webm::fix_duration(&bytes) {
+        Ok(fixed) => fixed,
+        // Still playable from the start, just not seekable
+        Err(error) => {
+            web_sys::console::warn_1(&format!("Could not fix the recording's duration: {error}").into());
+            return (blob, None);
+        }
+    };
+    let duration_ms = webm::Webm::parse(&fixed).ok().map(|parsed| parsed.duration_ms());
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(capability::container_type(mime_type));
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(fixed.as_slice()));
+    match web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options) {
+        Ok(fixed) => (fixed, duration_ms),
+        Err(_) => (blob, None),
+    }
+}
+
+/// Write markers into a WebM recording as chapters, leaving it as it was if that fails
+pub async fn add_chapters(blob: web_sys::Blob, mime_type: &str, markers: &[Marker]) -> web_sys::Blob {
+    if markers.is_empty() || capability::file_extension(mime_type) != "webm" {
+        return blob;
+    }
+    let Ok(buffer) = wasm_bindgen_futures::JsFuture::from(blob.array_buffer()).await else {
+        return blob;
+    };
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    let chapters: Vec<(f64, String)> = markers.iter().map(|marker| marker.time_ms).zip(markers::titles(markers)).collect();
+    let with_chapters = match webm::with_chapters(&bytes, &chapters) {
+        Ok(with_chapters) => with_chapters,
+        Err(error) => {
+            web_sys::console::warn_1(&format!("Could not write the markers as chapters: {error}").into());
+            return blob;
+        }
+    };
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(capability::container_type(mime_type));
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(with_chapters.as_slice()));
+    web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).unwrap_or(blob)
+}
+
+// Id for a new recording: its time, and a random part so recordings made in
+// the same millisecond, in this tab or another, don't share a file
+fn new_id(created_at: f64) -> String {
+    format!("{}-{:08x}", created_at as u64, (js_sys::Math::random() * f64::from(u32::MAX)) as u32)
+}
+
+/// Keep a recording in the library, named after the time it was made unless
+/// `name` is given. The blob is stored as given, so a WebM should have been
+/// through `make_seekable` first.
+pub async fn add(
+    blob: web_sys::Blob,
+    mime_type: &str,
+    name: Option<String>,
+    duration_ms: f64,
+    thumbnail: Option<String>,
+    markers: Vec<Marker>,
+) -> Result<SavedRecording, RecorderError> {
+    let blob = add_chapters(blob, mime_type, &markers).await;
+    let created_at = js_sys::Date::now();
+    let recording = SavedRecording {
+        id: new_id(created_at),
+        name: name.unwrap_or_else(|| default_name(created_at)),
+        mime_type: mime_type.to_string(),
+        created_at,
+        duration_ms,
+        size: blob.size(),
+        thumbnail,
+        markers,
+    };
+
+    let write = async {
+        let dir = opfs::path(&[RECORDINGS_DIR], true).await?;
+        opfs::write_file(&dir, &recording.stored_name(), &blob).await
+    };
+    write.await.map_err(|error| opfs::storage_error("the recording", &error))?;
+
+    let mut recordings = load_recordings();
+    recordings.insert(0, recording.clone());
+    save_recordings(&recordings)?;
+    Ok(recording)
+}
+
+/// The recorded video
+pub async fn file(recording: &SavedRecording) -> Result<web_sys::Blob, RecorderError> {
+    let read = async {
+        let dir = opfs::path(&[RECORDINGS_DIR], false).await?;
+        opfs::read_file(&dir, &recording.stored_name()).await
+    };
+    let file = read.await.map_err(|error| opfs::storage_error(&format!("\"{}\"", recording.name), &error))?;
+    Ok(file.unchecked_into())
+}
+
+pub fn rename(id: &str, name: &str) -> Result<(), RecorderError> {
+    let Some(name) = clean_name(name) else {
+        return Ok(());
+    };
+    let mut recordings = load_recordings();
+    if let Some(recording) = recordings.iter_mut().find(|recording| recording.id == id) {
+        recording.name = name;
+    }
+    save_recordings(&recordings)
+}
+
+pub async fn delete(id: &str) -> Result<(), RecorderError> {
+    let mut recordings = load_recordings();
+    let Some(index) = recordings.iter().position(|recording| recording.id == id) else {
+        return Ok(());
+    };
+    let recording = recordings.remove(index);
+    save_recordings(&recordings)?;
+
+    // Gone already is fine
+    if let Ok(dir) = opfs::path(&[RECORDINGS_DIR], false).await {
+        let _ = opfs::remove(&dir, &recording.stored_name()).await;
+    }
+    Ok(())
+}
+
+/// Join recordings, oldest first, into a new one in the library without
+/// re-encoding. Only works for WebM recordings made with the same settings.
+pub async fn join(recordings: &[SavedRecording]) -> Result<SavedRecording, RecorderError> {
+    let failed = |reason: String| RecorderError::RecordingFailed(format!("Could not join the recordings: {reason}"));
+    // Oldest first, which is the order they were recorded in
+    let mut recordings = recordings.to_vec();
+    recordings.sort_by(|a, b| a.created_at.total_cmp(&b.created_at));
+    let Some(first) = recordings.first().cloned() else {
+        return Err(failed("none are selected".into()));
+    };
+    if let Some(other) = recordings.iter().find(|recording| capability::file_extension(&recording.mime_type) != "webm") {
+        return Err(failed(format!("\"{}\" isn't WebM, and only WebM recordings can be joined", other.name)));
+    }
+
+    let mut files = Vec::new();
+    for recording in &recordings {
+        let buffer = wasm_bindgen_futures::JsFuture::from(file(recording).await?.array_buffer())
+            .await
+            .map_err(|_| failed(format!("\"{}\" is unreadable", recording.name)))?;
+        files.push(js_sys::Uint8Array::new(&buffer).to_vec());
+    }
+    let parts: Vec<&[u8]> = files.iter().map(Vec::as_slice).collect();
+    let (joined, kept) = webm::concat(&parts).map_err(|error| match error {
+        webm::WebmError::Mismatch { file, difference } => {
+            let name = recordings.get(file).map_or("", |recording| recording.name.as_str());
+            let difference = difference.replace("the first", &format!("\"{}\"", first.name));
+            failed(format!("\"{name}\" {difference}"))
+        }
+        other => failed(other.to_string()),
+    })?;
+    let duration_ms = webm::Webm::parse(&joined).map(|parsed| parsed.duration_ms()).unwrap_or(0.0);
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(capability::container_type(&first.mime_type));
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(joined.as_slice()));
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|_| failed("out of memory".into()))?;
+    // Each take's markers move along to where the take was placed
+    let joined_markers = kept
+        .iter()
+        .flat_map(|part| {
+            recordings[part.source].markers.iter().map(|marker| Marker {
+                time_ms: part.place(marker.time_ms.max(part.source_start_ms)),
+                label: marker.label.clone(),
+            })
+        })
+        .collect();
+    let name = format!("{} + {} more", first.name, recordings.len() - 1);
+    add(blob, &first.mime_type, Some(name), duration_ms, first.thumbnail.clone(), joined_markers).await
+}
+
+/// Bytes used by this site and the most it may use, from `navigator.storage.estimate()`
+pub async fn storage_estimate() -> Option<(f64, f64)> {
+    let promise = web_sys::window()?.navigator().storage().estimate().ok()?;
+    let estimate: web_sys::StorageEstimate = wasm_bindgen_futures::JsFuture::from(promise).await.ok()?.unchecked_into();
+    Some((estimate.get_usage()?, estimate.get_quota()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(0.0), "0:00");
+        assert_eq!(format_duration(65_400.0), "1:05");
+        assert_eq!(format_duration(3_723_000.0), "1:02:03");
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(820_000.0), "820 KB");
+        assert_eq!(format_size(12_500_000.0), "12.5 MB");
+        assert_eq!(format_size(2_000_000_000.0), "2.0 GB");
+    }
+
+    #[test]
+    fn cleans_names() {
+        assert_eq!(clean_name("  Intro take 2 ").as_deref(), Some("Intro take 2"));
+        assert_eq!(clean_name("a/b:c").as_deref(), Some("a-b-c"));
+        assert_eq!(clean_name("..."), None);
+        assert_eq!(clean_name("   "), None);
+    }
+}
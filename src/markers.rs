@@ -0,0 +1,195 @@
+//! Markers dropped while recording, kept with the take and exported as
+//! chapters: WebM Chapters in the file, WebVTT, or YouTube timestamps.
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+use crate::webm::KeptPart;
+
+const MARKER_FLASH_KEY: &str = "demrec.marker_flash";
+
+/// Key that drops a marker while recording
+pub const MARKER_HOTKEY: &str = "m";
+
+/// How long the preview flashes when a marker is dropped
+pub const FLASH_MS: u32 = 250;
+
+/// A point in a recording, in milliseconds from its start
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Marker {
+    pub time_ms: f64,
+    /// Empty until the presenter names it
+    #[serde(default)]
+    pub label: String,
+}
+
+impl Marker {
+    pub fn new(time_ms: f64) -> Self {
+        Self {
+            time_ms,
+            label: String::new(),
+        }
+    }
+}
+
+/// Labels to show and export, numbering the markers that have none
+pub fn titles(markers: &[Marker]) -> Vec<String> {
+    markers
+        .iter()
+        .enumerate()
+        .map(|(index, marker)| match marker.label.trim() {
+            "" => format!("Marker {}", index + 1),
+            label => label.to_string(),
+        })
+        .collect()
+}
+
+/// Where markers land once a take is cut down to the `kept` parts. Markers in
+/// the parts taken out are dropped.
+pub fn after_cuts(markers: &[Marker], kept: &[KeptPart]) -> Vec<Marker> {
+    markers
+        .iter()
+        .filter_map(|marker| {
+            let part = kept
+                .iter()
+                .find(|part| (part.source_start_ms..=part.source_end_ms).contains(&marker.time_ms))?;
+            Some(Marker {
+                time_ms: part.place(marker.time_ms),
+                label: marker.label.clone(),
+            })
+        })
+        .collect()
+}
+
+// "00:01:23.500", as WebVTT writes times
+fn vtt_time(ms: f64) -> String {
+    let ms = ms.round().max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}.{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+/// WebVTT chapters, each running from its marker to the next one or the end
+pub fn to_webvtt(markers: &[Marker], duration_ms: f64) -> String {
+    let titles = titles(markers);
+    let mut vtt = String::from("WEBVTT\n");
+    for (index, marker) in markers.iter().enumerate() {
+        let end = markers.get(index + 1).map_or(duration_ms, |next| next.time_ms).max(marker.time_ms);
+        vtt.push_str(&format!(
+            "\n{}\n{} --> {}\n{}\n",
+            index + 1,
+            vtt_time(marker.time_ms),
+            vtt_time(end),
+            titles[index]
+        ));
+    }
+    vtt
+}
+
+/// Timestamps for a YouTube description, "1:23 Setup" per line. YouTube wants
+/// the first at 0:00, so one is added if the first marker comes later.
+pub fn to_youtube(markers: &[Marker]) -> String {
+    let titles = titles(markers);
+    let mut lines = Vec::new();
+    if markers.first().is_none_or(|first| first.time_ms >= 1000.0) {
+        lines.push("0:00 Start".to_string());
+    }
+    for (marker, title) in markers.iter().zip(titles) {
+        let seconds = (marker.time_ms / 1000.0).floor().max(0.0) as u64;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        let time = if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            format!("{minutes}:{seconds:02}")
+        };
+        lines.push(format!("{time} {title}"));
+    }
+    lines.join("\n")
+}
+
+/// Whether dropping a marker flashes the preview
+pub fn load_marker_flash() -> bool {
+    storage::load_json(MARKER_FLASH_KEY).unwrap_or(true)
+}
+
+pub fn save_marker_flash(flash: bool) {
+    storage::save_json(MARKER_FLASH_KEY, &flash);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webm;
+
+    fn marker(time_ms: f64, label: &str) -> Marker {
+        Marker {
+            time_ms,
+            label: label.to_string(),
+        }
+    }
+
+    #[test]
+    fn unnamed_markers_are_numbered() {
+        assert_eq!(titles(&[marker(0.0, "Intro"), marker(5.0, "  ")]), vec!["Intro", "Marker 2"]);
+    }
+
+    #[test]
+    fn markers_follow_the_cuts() {
+        let markers = [marker(500.0, "a"), marker(2_500.0, "b"), marker(4_000.0, "c")];
+        let part = |source_start_ms, source_end_ms, start_ms| KeptPart {
+            source: 0,
+            source_start_ms,
+            source_end_ms,
+            start_ms,
+        };
+        let kept = [part(0.0, 2_000.0, 0.0), part(3_000.0, 5_000.0, 2_000.0)];
+        assert_eq!(after_cuts(&markers, &kept), vec![marker(500.0, "a"), marker(3_000.0, "c")]);
+    }
+
+    #[test]
+    fn chapters_land_on_the_kept_frames() {
+        const CHROME: &[u8] = include_bytes!("../tests/fixtures/chrome-vp9-opus.webm");
+        // Both parts start between keyframes, so they go back to the ones at 0 and 4 s
+        let (out, kept) = webm::cut(CHROME, &[(1_000.0, 2_500.0), (4_200.0, 6_000.0)]).unwrap();
+        let markers = [
+            marker(500.0, "Before the trim"),
+            marker(1_500.0, "Setup"),
+            marker(3_000.0, "Cut out"),
+            marker(4_000.0, "Keyframe"),
+            marker(5_000.0, "Demo"),
+        ];
+        let placed = after_cuts(&markers, &kept);
+        let chapters: Vec<(f64, String)> = placed.iter().map(|marker| marker.time_ms).zip(titles(&placed)).collect();
+        let with_chapters = webm::with_chapters(&out, &chapters).unwrap();
+
+        // The keyframe at 4 s follows the first part's last frame at 2500 ms
+        let saved = webm::Webm::parse(&with_chapters).unwrap();
+        let times: Vec<_> = saved.chapters().into_iter().map(|(ns, title)| (ns / 1_000_000, title)).collect();
+        assert_eq!(
+            times,
+            vec![
+                (500, "Before the trim".to_string()),
+                (1_500, "Setup".to_string()),
+                (2_533, "Keyframe".to_string()),
+                (3_533, "Demo".to_string()),
+            ]
+        );
+        assert!(saved.cues().iter().any(|&(time, _)| time == 2_533));
+    }
+
+    #[test]
+    fn writes_webvtt_chapters() {
+        let vtt = to_webvtt(&[marker(0.0, "Intro"), marker(83_500.0, "")], 3_725_000.0);
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n1\n00:00:00.000 --> 00:01:23.500\nIntro\n\n2\n00:01:23.500 --> 01:02:05.000\nMarker 2\n"
+        );
+    }
+
+    #[test]
+    fn writes_youtube_timestamps() {
+        assert_eq!(
+            to_youtube(&[marker(12_000.0, "Setup"), marker(3_725_900.0, "Wrap up")]),
+            "0:00 Start\n0:12 Setup\n1:02:05 Wrap up"
+        );
+        assert_eq!(to_youtube(&[marker(400.0, "Intro")]), "0:00 Intro");
+    }
+}
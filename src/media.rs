@@ -0,0 +1,187 @@
+use wasm_bindgen::JsCast;
+
+use crate::error::{Device, RecorderError};
+
+fn media_devices(device: Device) -> Result<web_sys::MediaDevices, RecorderError> {
+    web_sys::window()
+        .and_then(|window| window.navigator().media_devices().ok())
+        .ok_or_else(|| RecorderError::Unsupported(format!("Capturing the {}", device.label())))
+}
+
+async fn into_stream(device: Device, promise: js_sys::Promise) -> Result<web_sys::MediaStream, RecorderError> {
+    let stream = wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(|error| RecorderError::from_media_error(device, &error))?;
+    stream
+        .dyn_into::<web_sys::MediaStream>()
+        .map_err(|_| RecorderError::Unsupported(format!("Capturing the {}", device.label())))
+}
+
+/// Ask for a camera or microphone stream, `device` naming it in errors
+pub async fn user_media(
+    device: Device,
+    constraints: &web_sys::MediaStreamConstraints,
+) -> Result<web_sys::MediaStream, RecorderError> {
+    let promise = media_devices(device)?
+        .get_user_media_with_constraints(constraints)
+        .map_err(|error| RecorderError::from_media_error(device, &error))?;
+    into_stream(device, promise).await
+}
+
+/// Ask the user to pick a screen, window or tab to share
+pub async fn display_media() -> Result<web_sys::MediaStream, RecorderError> {
+    let promise = media_devices(Device::Screen)?
+        .get_display_media()
+        .map_err(|error| RecorderError::from_media_error(Device::Screen, &error))?;
+    into_stream(Device::Screen, promise).await
+}
+
+/// Whether a track of the stream is still delivering media
+pub fn is_live(stream: &web_sys::MediaStream) -> bool {
+    let tracks = stream.get_tracks();
+    (0..tracks.length()).any(|i| {
+        tracks
+            .get(i)
+            .dyn_into::<web_sys::MediaStreamTrack>()
+            .is_ok_and(|track| track.ready_state() == web_sys::MediaStreamTrackState::Live)
+    })
+}
+
+/// Id of the device the stream's first track comes from
+pub fn stream_device_id(stream: &web_sys::MediaStream) -> Option<String> {
+    stream
+        .get_tracks()
+        .get(0)
+        .dyn_into::<web_sys::MediaStreamTrack>()
+        .ok()?
+        .get_settings()
+        .get_device_id()
+}
+
+/// What the browser will do when the page asks for a device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    Granted,
+    /// The browser will ask the user
+    Prompt,
+    Denied,
+    /// The Permissions API can't tell (not supported for this device or browser)
+    Unknown,
+}
+
+impl Permission {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Permission::Granted => "Allowed",
+            Permission::Prompt => "Not asked yet",
+            Permission::Denied => "Blocked",
+            Permission::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Ask the Permissions API about a device without prompting the user
+pub async fn query_permission(device: Device) -> Permission {
+    let name = match device {
+        Device::Camera => "camera",
+        Device::Microphone => "microphone",
+        // There is no permission to query for screen sharing, it is asked every time
+        Device::Screen => return Permission::Unknown,
+    };
+    let Some(permissions) = web_sys::window().and_then(|window| window.navigator().permissions().ok()) else {
+        return Permission::Unknown;
+    };
+
+    let descriptor = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&descriptor, &"name".into(), &name.into());
+    let Ok(promise) = permissions.query(&descriptor) else {
+        return Permission::Unknown;
+    };
+    let status = wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .ok()
+        .and_then(|status| status.dyn_into::<web_sys::PermissionStatus>().ok());
+
+    match status.map(|status| status.state()) {
+        Some(web_sys::PermissionState::Granted) => Permission::Granted,
+        Some(web_sys::PermissionState::Prompt) => Permission::Prompt,
+        Some(web_sys::PermissionState::Denied) => Permission::Denied,
+        _ => Permission::Unknown,
+    }
+}
+
+/// A camera or microphone the browser knows about
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputDevice {
+    pub id: String,
+    /// Empty until the page has been given access to devices of this kind
+    pub label: String,
+    pub device: Device,
+}
+
+/// Cameras and microphones connected to the computer
+pub async fn list_input_devices() -> Vec<InputDevice> {
+    let Ok(promise) = media_devices(Device::Camera).and_then(|media_devices| {
+        media_devices
+            .enumerate_devices()
+            .map_err(|_| RecorderError::Unsupported("Listing devices".into()))
+    }) else {
+        return Vec::new();
+    };
+    let Ok(list) = wasm_bindgen_futures::JsFuture::from(promise).await else {
+        return Vec::new();
+    };
+
+    js_sys::Array::from(&list)
+        .iter()
+        .filter_map(|info| info.dyn_into::<web_sys::MediaDeviceInfo>().ok())
+        .filter_map(|info| {
+            let device = match info.kind() {
+                web_sys::MediaDeviceKind::Videoinput => Device::Camera,
+                web_sys::MediaDeviceKind::Audioinput => Device::Microphone,
+                _ => return None,
+            };
+            Some(InputDevice {
+                id: info.device_id(),
+                label: info.label(),
+                device,
+            })
+        })
+        .collect()
+}
+
+/// Measures how loud a microphone stream is, for a level meter
+pub struct LevelMeter {
+    context: web_sys::AudioContext,
+    analyser: web_sys::AnalyserNode,
+}
+
+impl LevelMeter {
+    pub fn new(stream: &web_sys::MediaStream) -> Option<Self> {
+        let context = web_sys::AudioContext::new().ok()?;
+        let source = context.create_media_stream_source(stream).ok()?;
+        let analyser = context.create_analyser().ok()?;
+        analyser.set_fft_size(512);
+        source.connect_with_audio_node(&analyser).ok()?;
+        Some(Self { context, analyser })
+    }
+
+    /// Current loudness from 0 (silent) to 1
+    pub fn level(&self) -> f64 {
+        let mut samples = vec![0u8; self.analyser.fft_size() as usize];
+        self.analyser.get_byte_time_domain_data(&mut samples);
+        let sum: f64 = samples
+            .iter()
+            .map(|&sample| {
+                let value = (sample as f64 - 128.0) / 128.0;
+                value * value
+            })
+            .sum();
+        // Scale up so normal speech fills most of the meter
+        ((sum / samples.len().max(1) as f64).sqrt() * 4.0).min(1.0)
+    }
+
+    pub fn close(&self) {
+        let _ = self.context.close();
+    }
+}
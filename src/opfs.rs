@@ -0,0 +1,78 @@
+//! Origin private file system: files kept by the browser for this site, out of
+//! the user's sight, that survive a crash or reload.
+
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::error::RecorderError;
+
+async fn resolve<T: JsCast>(promise: js_sys::Promise) -> Result<T, JsValue> {
+    JsFuture::from(promise).await?.dyn_into::<T>()
+}
+
+/// Error for a failed file operation, `what` naming what couldn't be saved
+pub fn storage_error(what: &str, error: &JsValue) -> RecorderError {
+    let name = js_sys::Reflect::get(error, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+        .unwrap_or_default();
+    match name.as_str() {
+        "QuotaExceededError" => RecorderError::StorageFull(what.to_string()),
+        "NotFoundError" => RecorderError::RecordingFailed(format!("Could not find {what}")),
+        _ => RecorderError::RecordingFailed(format!("Could not save {what} ({name})")),
+    }
+}
+
+/// Top directory of the origin private file system
+pub async fn root() -> Result<web_sys::FileSystemDirectoryHandle, JsValue> {
+    let storage = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no window"))?
+        .navigator()
+        .storage();
+    resolve(storage.get_directory()).await
+}
+
+/// Subdirectory `name` of `parent`, created if `create` is set
+pub async fn directory(
+    parent: &web_sys::FileSystemDirectoryHandle,
+    name: &str,
+    create: bool,
+) -> Result<web_sys::FileSystemDirectoryHandle, JsValue> {
+    let options = web_sys::FileSystemGetDirectoryOptions::new();
+    options.set_create(create);
+    resolve(parent.get_directory_handle_with_options(name, &options)).await
+}
+
+/// Directory at a path below the root, such as `["sessions", "1700000000000"]`
+pub async fn path(names: &[&str], create: bool) -> Result<web_sys::FileSystemDirectoryHandle, JsValue> {
+    let mut dir = root().await?;
+    for name in names {
+        dir = directory(&dir, name, create).await?;
+    }
+    Ok(dir)
+}
+
+/// Write a whole file, replacing it if it exists. The data is on disk once this returns.
+pub async fn write_file(dir: &web_sys::FileSystemDirectoryHandle, name: &str, data: &web_sys::Blob) -> Result<(), JsValue> {
+    let options = web_sys::FileSystemGetFileOptions::new();
+    options.set_create(true);
+    let file: web_sys::FileSystemFileHandle = resolve(dir.get_file_handle_with_options(name, &options)).await?;
+    let stream: web_sys::FileSystemWritableFileStream = resolve(file.create_writable()).await?;
+    JsFuture::from(stream.write_with_blob(data)?).await?;
+    JsFuture::from(stream.close()).await?;
+    Ok(())
+}
+
+/// Contents of a file
+pub async fn read_file(dir: &web_sys::FileSystemDirectoryHandle, name: &str) -> Result<web_sys::File, JsValue> {
+    let file: web_sys::FileSystemFileHandle = resolve(dir.get_file_handle(name)).await?;
+    resolve(file.get_file()).await
+}
+
+/// Delete a file, or a directory with everything in it
+pub async fn remove(dir: &web_sys::FileSystemDirectoryHandle, name: &str) -> Result<(), JsValue> {
+    let options = web_sys::FileSystemRemoveOptions::new();
+    options.set_recursive(true);
+    JsFuture::from(dir.remove_entry_with_options(name, &options)).await?;
+    Ok(())
+}
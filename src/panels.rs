@@ -0,0 +1,675 @@
+//! Overlays shown over the recorder: the readiness check before recording,
+//! the review of a take after it stops, and the library of kept recordings.
+
+use dioxus::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::{Device, RecorderError};
+use crate::library::{self, SavedRecording};
+use crate::markers;
+use crate::media::{self, InputDevice, Permission};
+use crate::review::{self, Review};
+
+// Width and padding of the review card
+const REVIEW_CARD_WIDTH: f64 = 672.0;
+const REVIEW_CARD_PADDING: f64 = 24.0;
+// Width of the review waveform, filling the card, for mapping clicks on it to times
+const REVIEW_WAVEFORM_WIDTH: f64 = REVIEW_CARD_WIDTH - 2.0 * REVIEW_CARD_PADDING;
+
+/// Permissions, devices and previews of each source, with the button that starts recording
+#[component]
+pub fn ReadinessPanel(
+    mut show_readiness: Signal<bool>,
+    mut show_capabilities: Signal<bool>,
+    mic_stream: Signal<Option<web_sys::MediaStream>>,
+    camera_stream: Signal<Option<web_sys::MediaStream>>,
+    is_screen_sharing: Signal<bool>,
+    camera_enabled: Signal<bool>,
+    mic_permission: Signal<Permission>,
+    camera_permission: Signal<Permission>,
+    input_devices: Signal<Vec<InputDevice>>,
+    /// Microphone loudness from 0 to 1
+    mic_level: Signal<f64>,
+    /// Called to start or share a source that isn't live yet
+    on_allow: EventHandler<Device>,
+    on_record: EventHandler<()>,
+) -> Element {
+    // Something to record and a microphone to record it with
+    let sources_ready =
+        mic_stream().is_some_and(|stream| media::is_live(&stream)) && (is_screen_sharing() || camera_enabled());
+
+    rsx! {
+        div {
+            style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.7); z-index: 800; display: flex; align-items: center; justify-content: center;",
+            div {
+                style: "background-color: #1f2937; color: white; padding: 24px; border-radius: 16px; width: 640px; max-height: 90vh; overflow-y: auto; display: flex; flex-direction: column; gap: 16px; font-family: system-ui, sans-serif; font-size: 13px; box-shadow: 0 20px 25px -5px rgba(0, 0, 0, 0.5);",
+                h2 {
+                    style: "margin: 0; font-size: 20px;",
+                    "Ready to record?"
+                }
+                p {
+                    style: "margin: 0; color: #9ca3af;",
+                    "Recording needs the microphone and the screen or the camera. Allow them now so no browser prompt interrupts the take."
+                }
+
+                for (device, live, permission) in [
+                    (Device::Microphone, mic_stream().is_some_and(|stream| media::is_live(&stream)), mic_permission()),
+                    (Device::Screen, is_screen_sharing(), Permission::Unknown),
+                    (Device::Camera, camera_enabled(), camera_permission()),
+                ] {
+                    div {
+                        key: "{device.label()}",
+                        style: "display: flex; gap: 16px; align-items: flex-start; border-top: 1px solid #374151; padding-top: 12px;",
+                        div {
+                            style: "flex: 1; display: flex; flex-direction: column; gap: 6px;",
+                            div {
+                                style: "display: flex; align-items: center; gap: 8px;",
+                                strong {
+                                    style: "text-transform: capitalize; font-size: 14px;",
+                                    "{device.label()}"
+                                }
+                                span {
+                                    style: format!("padding: 2px 8px; border-radius: 999px; font-size: 11px; background-color: {};",
+                                        if live { "#065f46" } else if permission == Permission::Denied { "#7f1d1d" } else { "#374151" }
+                                    ),
+                                    {if live { "Live" } else if device == Device::Screen { "Not shared" } else { permission.label() }}
+                                }
+                                if device == Device::Microphone {
+                                    span { style: "color: #9ca3af; font-size: 11px;", "required" }
+                                }
+                            }
+                            if permission == Permission::Denied {
+                                div {
+                                    style: "color: #fca5a5;",
+                                    {RecorderError::PermissionDenied(device).hint()}
+                                }
+                            }
+                            for input in input_devices().into_iter().filter(|input| input.device == device) {
+                                div {
+                                    key: "{input.id}",
+                                    style: "color: #d1d5db;",
+                                    {
+                                        let stream = if device == Device::Camera { camera_stream() } else { mic_stream() };
+                                        let active = stream.and_then(|stream| media::stream_device_id(&stream)).as_deref() == Some(input.id.as_str());
+                                        let name = if input.label.is_empty() { format!("Unnamed {}", device.label()) } else { input.label.clone() };
+                                        if active { format!("● {name}") } else { format!("○ {name}") }
+                                    }
+                                }
+                            }
+                            if !live {
+                                button {
+                                    style: "align-self: flex-start; background-color: #3b82f6; color: white; border: none; padding: 6px 14px; border-radius: 6px; cursor: pointer;",
+                                    onclick: move |_| on_allow.call(device),
+                                    {if device == Device::Screen { "Choose screen" } else { "Allow" }}
+                                }
+                            }
+                        }
+                        // Test preview
+                        match device {
+                            Device::Microphone => rsx! {
+                                div {
+                                    style: "width: 200px; height: 8px; margin-top: 6px; background-color: #111827; border-radius: 4px; overflow: hidden;",
+                                    div {
+                                        style: format!("width: {:.0}%; height: 100%; background-color: #10b981; transition: width 0.1s;", mic_level() * 100.0),
+                                    }
+                                }
+                            },
+                            Device::Screen => rsx! {
+                                video {
+                                    id: "readiness-screen",
+                                    style: "width: 200px; height: 112px; background-color: #111827; border-radius: 6px; object-fit: contain;",
+                                    autoplay: "true",
+                                    playsinline: "true",
+                                    muted: "true",
+                                }
+                            },
+                            Device::Camera => rsx! {
+                                video {
+                                    id: "readiness-camera",
+                                    style: "width: 200px; height: 112px; background-color: #111827; border-radius: 6px; object-fit: cover; transform: scaleX(-1);",
+                                    autoplay: "true",
+                                    playsinline: "true",
+                                    muted: "true",
+                                }
+                            },
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex; gap: 12px; justify-content: flex-end; border-top: 1px solid #374151; padding-top: 16px;",
+                    button {
+                        style: "margin-right: auto; background: none; border: none; color: #93c5fd; cursor: pointer;",
+                        onclick: move |_| show_capabilities.set(true),
+                        "Browser capabilities"
+                    }
+                    button {
+                        style: "background-color: #6b7280; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                        onclick: move |_| show_readiness.set(false),
+                        "Cancel"
+                    }
+                    button {
+                        style: format!("background-color: {}; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                            if sources_ready { "#dc2626" } else { "#4b5563" }
+                        ),
+                        disabled: !sources_ready,
+                        onclick: move |_| {
+                            show_readiness.set(false);
+                            on_record.call(());
+                        },
+                        "⏺ Record"
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The review player, looked up by id since it is only in the page while reviewing
+fn review_video() -> Option<web_sys::HtmlVideoElement> {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|document| document.get_element_by_id("review-video"))
+        .and_then(|element| element.dyn_into::<web_sys::HtmlVideoElement>().ok())
+}
+
+/// The take just recorded, with its waveform, markers, trim and cut controls
+#[component]
+pub fn ReviewPanel(
+    mut review: Signal<Option<Review>>,
+    /// Playhead in milliseconds
+    mut review_position: Signal<f64>,
+    mut is_review_playing: Signal<bool>,
+    is_saving_review: Signal<bool>,
+    on_discard: EventHandler<()>,
+    on_retake: EventHandler<()>,
+    on_save: EventHandler<()>,
+) -> Element {
+    let Some(current) = review() else {
+        return rsx! {};
+    };
+
+    let mut seek_review = move |ms: f64| {
+        if let Some(video) = review_video() {
+            video.set_current_time(ms / 1000.0);
+        }
+        review_position.set(ms);
+    };
+
+    rsx! {
+        div {
+            style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.8); z-index: 790; display: flex; align-items: center; justify-content: center;",
+            div {
+                style: format!("background-color: #1f2937; color: white; padding: {REVIEW_CARD_PADDING}px; border-radius: 16px; width: {REVIEW_CARD_WIDTH}px; max-height: 90vh; display: flex; flex-direction: column; gap: 12px; font-family: system-ui, sans-serif; font-size: 13px; box-shadow: 0 20px 25px -5px rgba(0, 0, 0, 0.5);"),
+                div {
+                    style: "display: flex; align-items: center;",
+                    h2 {
+                        style: "margin: 0; flex: 1; font-size: 20px;",
+                        "Review take"
+                    }
+                    span {
+                        style: "color: #9ca3af; font-variant-numeric: tabular-nums;",
+                        {format!("{} / {}", library::format_duration(review_position()), library::format_duration(current.duration_ms))}
+                    }
+                }
+                video {
+                    id: "review-video",
+                    style: "width: 100%; max-height: 50vh; background-color: black; border-radius: 8px; cursor: pointer;",
+                    src: "{current.url}",
+                    onclick: move |_| {
+                        if let Some(video) = review_video() {
+                            if video.paused() {
+                                let _ = video.play();
+                            } else {
+                                let _ = video.pause();
+                            }
+                        }
+                    },
+                    onplay: move |_| is_review_playing.set(true),
+                    onpause: move |_| is_review_playing.set(false),
+                    ontimeupdate: move |_| {
+                        if let Some(video) = review_video() {
+                            let ms = video.current_time() * 1000.0;
+                            review_position.set(ms);
+                            // Stop at the end of the trim range and jump over cuts while editing
+                            let (trim, skip) = review
+                                .peek()
+                                .as_ref()
+                                .map(|current| (current.trim, review::skip_cut(&current.cuts, ms)))
+                                .unwrap_or_default();
+                            if let Some((_, end)) = trim {
+                                if ms >= end && !video.paused() {
+                                    let _ = video.pause();
+                                }
+                            }
+                            if let Some(to) = skip.filter(|_| !video.paused()) {
+                                video.set_current_time(to / 1000.0);
+                            }
+                        }
+                    },
+                }
+
+                // Waveform with the playhead; click or drag to scrub
+                div {
+                    style: format!("position: relative; width: {REVIEW_WAVEFORM_WIDTH}px; height: 56px; background-color: #111827; border-radius: 6px; cursor: pointer; display: flex; align-items: center; gap: 1px; padding: 0; overflow: hidden;"),
+                    onmousedown: move |event| {
+                        let fraction = (event.element_coordinates().x / REVIEW_WAVEFORM_WIDTH).clamp(0.0, 1.0);
+                        seek_review(fraction * current.duration_ms);
+                    },
+                    onmousemove: move |event| {
+                        if event.held_buttons().contains(dioxus::html::input_data::MouseButton::Primary) {
+                            let fraction = (event.element_coordinates().x / REVIEW_WAVEFORM_WIDTH).clamp(0.0, 1.0);
+                            seek_review(fraction * current.duration_ms);
+                        }
+                    },
+                    for (i, level) in current.waveform.iter().enumerate() {
+                        div {
+                            key: "{i}",
+                            style: format!("flex: 1; height: {}%; min-height: 2px; background-color: #818cf8; border-radius: 1px; pointer-events: none;", level * 90.0),
+                        }
+                    }
+                    if let Some((start, end)) = current.trim {
+                        div {
+                            style: format!("position: absolute; top: 0; left: 0; height: 100%; width: {}%; background-color: rgba(0, 0, 0, 0.6); pointer-events: none;",
+                                start / current.duration_ms.max(1.0) * 100.0
+                            ),
+                        }
+                        div {
+                            style: format!("position: absolute; top: 0; right: 0; height: 100%; width: {}%; background-color: rgba(0, 0, 0, 0.6); pointer-events: none;",
+                                (1.0 - end / current.duration_ms.max(1.0)) * 100.0
+                            ),
+                        }
+                    }
+                    for (i, (start, end)) in current.cuts.iter().copied().enumerate() {
+                        div {
+                            key: "cut-{i}",
+                            style: format!("position: absolute; top: 0; left: {}%; height: 100%; width: {}%; background-color: rgba(220, 38, 38, 0.45); pointer-events: none;",
+                                start.min(end) / current.duration_ms.max(1.0) * 100.0,
+                                (end - start).abs() / current.duration_ms.max(1.0) * 100.0
+                            ),
+                        }
+                    }
+                    for (i, marker) in current.markers.iter().enumerate() {
+                        div {
+                            key: "marker-{i}",
+                            style: format!("position: absolute; top: 0; left: {}%; width: 2px; height: 100%; background-color: #fbbf24; pointer-events: none;",
+                                (marker.time_ms / current.duration_ms.max(1.0) * 100.0).min(100.0)
+                            ),
+                        }
+                    }
+                    if let Some(cut_start) = current.cut_start {
+                        div {
+                            style: format!("position: absolute; top: 0; left: {}%; height: 100%; width: {}%; background-color: rgba(220, 38, 38, 0.25); border-left: 2px dashed #f87171; pointer-events: none;",
+                                cut_start.min(review_position()) / current.duration_ms.max(1.0) * 100.0,
+                                (review_position() - cut_start).abs() / current.duration_ms.max(1.0) * 100.0
+                            ),
+                        }
+                    }
+                    div {
+                        style: format!("position: absolute; top: 0; left: {}%; width: 2px; height: 100%; background-color: white; pointer-events: none;",
+                            (review_position() / current.duration_ms.max(1.0) * 100.0).min(100.0)
+                        ),
+                    }
+                }
+
+                if !current.markers.is_empty() {
+                    div {
+                        style: "display: flex; flex-wrap: wrap; gap: 6px;",
+                        for (i, (marker, title)) in current.markers.iter().zip(markers::titles(&current.markers)).enumerate() {
+                            button {
+                                key: "{i}",
+                                style: "background-color: rgba(251, 191, 36, 0.15); color: #fcd34d; border: none; border-radius: 4px; padding: 2px 8px; cursor: pointer; font-size: 12px;",
+                                title: "Play from this marker",
+                                onclick: {
+                                    let time_ms = marker.time_ms;
+                                    move |_| seek_review(time_ms)
+                                },
+                                {format!("🔖 {} {title}", library::format_duration(marker.time_ms))}
+                            }
+                        }
+                    }
+                }
+
+                if let Some((start, end)) = current.trim {
+                    div {
+                        style: "display: flex; align-items: center; gap: 8px;",
+                        button {
+                            onclick: move |_| {
+                                if let Some(trimmed) = review.write().as_mut() {
+                                    trimmed.trim = Some(review::clamp_trim(review_position(), end, trimmed.duration_ms));
+                                }
+                            },
+                            "Set start here"
+                        }
+                        button {
+                            onclick: move |_| {
+                                if let Some(trimmed) = review.write().as_mut() {
+                                    trimmed.trim = Some(review::clamp_trim(start, review_position(), trimmed.duration_ms));
+                                }
+                            },
+                            "Set end here"
+                        }
+                        if current.cut_start.is_some() {
+                            button {
+                                onclick: move |_| {
+                                    if let Some(edited) = review.write().as_mut() {
+                                        if let Some(cut_start) = edited.cut_start.take() {
+                                            let position = review_position().clamp(0.0, edited.duration_ms);
+                                            edited.cuts.push((cut_start.min(position), cut_start.max(position)));
+                                        }
+                                    }
+                                },
+                                "Cut to here"
+                            }
+                        } else {
+                            button {
+                                title: "Mark the start of a part to take out, then play or scrub to its end",
+                                onclick: move |_| {
+                                    if let Some(edited) = review.write().as_mut() {
+                                        edited.cut_start = Some(review_position());
+                                    }
+                                },
+                                "Cut from here"
+                            }
+                        }
+                        span {
+                            style: "flex: 1; color: #9ca3af;",
+                            {if current.cuts.is_empty() {
+                                format!("Keeping {} – {}", library::format_duration(start), library::format_duration(end))
+                            } else {
+                                format!("Keeping about {} of {}", library::format_duration(current.kept_ms()), library::format_duration(current.duration_ms))
+                            }}
+                        }
+                        button {
+                            onclick: move |_| seek_review(start),
+                            "Play from start"
+                        }
+                    }
+                    if !current.cuts.is_empty() {
+                        div {
+                            style: "display: flex; flex-wrap: wrap; gap: 6px;",
+                            for (i, (cut_start, cut_end)) in current.cuts.iter().copied().enumerate() {
+                                span {
+                                    key: "{i}",
+                                    style: "display: inline-flex; align-items: center; gap: 6px; background-color: rgba(220, 38, 38, 0.2); color: #fca5a5; padding: 2px 4px 2px 8px; border-radius: 4px;",
+                                    {format!("Cut {} – {}", library::format_duration(cut_start), library::format_duration(cut_end))}
+                                    button {
+                                        style: "background: none; border: none; color: #fca5a5; cursor: pointer; padding: 0 4px;",
+                                        title: "Keep this part after all",
+                                        onclick: move |_| {
+                                            if let Some(edited) = review.write().as_mut() {
+                                                if i < edited.cuts.len() {
+                                                    edited.cuts.remove(i);
+                                                }
+                                            }
+                                        },
+                                        "×"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    p {
+                        style: "margin: 0; color: #6b7280; font-size: 11px;",
+                        "Each kept part starts at the nearest keyframe before it, so a little more may be kept."
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 10px; margin-top: 4px;",
+                    button {
+                        style: format!("background-color: {}; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                            if current.trim.is_some() { "#8b5cf6" } else { "#4b5563" }
+                        ),
+                        disabled: !current.can_trim(),
+                        title: if current.can_trim() { "Trim the start and end, or cut parts out" } else { "Only WebM recordings can be trimmed" },
+                        onclick: move |_| {
+                            if let Some(trimmed) = review.write().as_mut() {
+                                trimmed.trim = match trimmed.trim {
+                                    Some(_) => None,
+                                    None => Some((0.0, trimmed.duration_ms)),
+                                };
+                                trimmed.cuts.clear();
+                                trimmed.cut_start = None;
+                            }
+                        },
+                        "✂ Trim"
+                    }
+                    button {
+                        style: "background-color: #6b7280; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                        disabled: is_saving_review(),
+                        onclick: move |_| on_discard.call(()),
+                        "Discard"
+                    }
+                    button {
+                        style: "background-color: #6b7280; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                        disabled: is_saving_review(),
+                        onclick: move |_| on_retake.call(()),
+                        "Discard & Retake"
+                    }
+                    button {
+                        style: "background-color: #059669; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                        disabled: is_saving_review(),
+                        onclick: move |_| on_save.call(()),
+                        {if is_saving_review() { "Saving…" } else if current.is_edited() { "Save edited" } else { "Save" }}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Recordings kept in the browser, to play, rename, download, join or delete
+#[component]
+pub fn LibraryPanel(
+    mut show_library: Signal<bool>,
+    library_recordings: Signal<Vec<SavedRecording>>,
+    /// Ids ticked for joining
+    mut selected_recordings: Signal<Vec<String>>,
+    is_joining: Signal<bool>,
+    /// Bytes used and allowed
+    storage_estimate: Signal<Option<(f64, f64)>>,
+    /// Id and object URL of the recording in the player
+    playing_recording: Signal<Option<(String, String)>>,
+    on_close_player: EventHandler<()>,
+    on_play: EventHandler<SavedRecording>,
+    on_download: EventHandler<SavedRecording>,
+    /// Called with the recording and whether to write YouTube timestamps rather than WebVTT
+    on_download_chapters: EventHandler<(SavedRecording, bool)>,
+    /// Called with the id and the new name
+    on_rename: EventHandler<(String, String)>,
+    on_delete: EventHandler<SavedRecording>,
+    on_join: EventHandler<()>,
+) -> Element {
+    rsx! {
+        div {
+            style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.7); z-index: 780; display: flex; align-items: center; justify-content: center;",
+            onclick: move |_| {
+                on_close_player.call(());
+                show_library.set(false);
+            },
+            div {
+                style: "background-color: #1f2937; color: white; padding: 24px; border-radius: 16px; width: 720px; max-height: 90vh; display: flex; flex-direction: column; gap: 12px; font-family: system-ui, sans-serif; font-size: 13px; box-shadow: 0 20px 25px -5px rgba(0, 0, 0, 0.5);",
+                onclick: move |event| event.stop_propagation(),
+                div {
+                    style: "display: flex; align-items: center; gap: 12px;",
+                    h2 {
+                        style: "margin: 0; flex: 1; font-size: 20px;",
+                        "Recordings"
+                    }
+                    if let Some((used, quota)) = storage_estimate() {
+                        span {
+                            style: "color: #9ca3af;",
+                            {format!("{} of {} used", library::format_size(used), library::format_size(quota))}
+                        }
+                        div {
+                            style: "width: 100px; height: 6px; background-color: #374151; border-radius: 3px; overflow: hidden;",
+                            div {
+                                style: format!("height: 100%; width: {}%; background-color: {};",
+                                    (used / quota.max(1.0) * 100.0).min(100.0),
+                                    if used > quota * 0.9 { "#ef4444" } else { "#6366f1" }
+                                ),
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            on_close_player.call(());
+                            show_library.set(false);
+                        },
+                        "Close"
+                    }
+                }
+
+                if let Some((_, url)) = playing_recording() {
+                    div {
+                        style: "display: flex; flex-direction: column; gap: 6px;",
+                        video {
+                            style: "width: 100%; max-height: 50vh; background-color: black; border-radius: 8px;",
+                            src: "{url}",
+                            controls: true,
+                            autoplay: true,
+                        }
+                        button {
+                            style: "align-self: flex-end;",
+                            onclick: move |_| on_close_player.call(()),
+                            "Close player"
+                        }
+                    }
+                }
+
+                if selected_recordings.read().len() > 1 {
+                    div {
+                        style: "display: flex; align-items: center; gap: 10px; padding: 8px 12px; border-radius: 8px; background-color: #312e81;",
+                        span {
+                            style: "flex: 1;",
+                            {format!("{} recordings selected, joined oldest first", selected_recordings.read().len())}
+                        }
+                        button {
+                            onclick: move |_| selected_recordings.set(Vec::new()),
+                            "Clear"
+                        }
+                        button {
+                            style: "background-color: #6366f1; color: white; border: none; padding: 6px 14px; border-radius: 6px; cursor: pointer; font-weight: 600;",
+                            disabled: is_joining(),
+                            title: "Join into one recording without re-encoding; they must be WebM recorded with the same settings",
+                            onclick: move |_| on_join.call(()),
+                            {if is_joining() { "Joining…" } else { "Join" }}
+                        }
+                    }
+                }
+
+                div {
+                    style: "overflow-y: auto; display: flex; flex-direction: column; gap: 8px;",
+                    if library_recordings.read().is_empty() {
+                        p {
+                            style: "margin: 0; color: #9ca3af;",
+                            "Recordings you make are kept here."
+                        }
+                    }
+                    for saved in library_recordings() {
+                        div {
+                            key: "{saved.id}",
+                            style: format!("display: flex; align-items: center; gap: 12px; padding: 8px; border-radius: 8px; background-color: {};",
+                                if playing_recording().is_some_and(|(id, _)| id == saved.id) { "#312e81" } else { "#111827" }
+                            ),
+                            input {
+                                r#type: "checkbox",
+                                title: "Select to join with other recordings",
+                                checked: selected_recordings.read().contains(&saved.id),
+                                onchange: {
+                                    let id = saved.id.clone();
+                                    move |event: FormEvent| {
+                                        let mut selected = selected_recordings.write();
+                                        selected.retain(|other| *other != id);
+                                        if event.checked() {
+                                            selected.push(id.clone());
+                                        }
+                                    }
+                                },
+                            }
+                            if let Some(thumbnail) = saved.thumbnail.clone() {
+                                img {
+                                    style: "width: 120px; height: 68px; object-fit: cover; border-radius: 4px; cursor: pointer;",
+                                    src: "{thumbnail}",
+                                    onclick: {
+                                        let saved = saved.clone();
+                                        move |_| on_play.call(saved.clone())
+                                    },
+                                }
+                            } else {
+                                div { style: "width: 120px; height: 68px; border-radius: 4px; background-color: #374151;" }
+                            }
+                            div {
+                                style: "flex: 1; display: flex; flex-direction: column; gap: 4px;",
+                                input {
+                                    style: "background-color: transparent; color: white; border: 1px solid #374151; border-radius: 4px; padding: 4px 6px; font-size: 14px;",
+                                    value: "{saved.name}",
+                                    onchange: {
+                                        let id = saved.id.clone();
+                                        move |event: FormEvent| on_rename.call((id.clone(), event.value()))
+                                    },
+                                }
+                                span {
+                                    style: "color: #9ca3af; font-size: 11px;",
+                                    {format!("{} · {} · {}{}",
+                                        String::from(js_sys::Date::new(&saved.created_at.into()).to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)),
+                                        library::format_duration(saved.duration_ms),
+                                        library::format_size(saved.size),
+                                        match saved.markers.len() {
+                                            0 => String::new(),
+                                            1 => " · 1 marker".to_string(),
+                                            count => format!(" · {count} markers"),
+                                        }
+                                    )}
+                                }
+                            }
+                            button {
+                                title: "Play",
+                                onclick: {
+                                    let saved = saved.clone();
+                                    move |_| on_play.call(saved.clone())
+                                },
+                                "▶"
+                            }
+                            button {
+                                title: "Download",
+                                onclick: {
+                                    let saved = saved.clone();
+                                    move |_| on_download.call(saved.clone())
+                                },
+                                "⬇"
+                            }
+                            if !saved.markers.is_empty() {
+                                button {
+                                    title: "Download the markers as WebVTT chapters",
+                                    onclick: {
+                                        let saved = saved.clone();
+                                        move |_| on_download_chapters.call((saved.clone(), false))
+                                    },
+                                    "VTT"
+                                }
+                                button {
+                                    title: "Download the markers as timestamps for a YouTube description",
+                                    onclick: {
+                                        let saved = saved.clone();
+                                        move |_| on_download_chapters.call((saved.clone(), true))
+                                    },
+                                    "YT"
+                                }
+                            }
+                            button {
+                                title: "Delete",
+                                onclick: {
+                                    let saved = saved.clone();
+                                    move |_| on_delete.call(saved.clone())
+                                },
+                                "🗑"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
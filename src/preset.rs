@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::redact::RedactionRegion;
+use crate::storage;
+
+const PRESETS_KEY: &str = "demrec.presets";
+
+/// A named set of settings that can be saved and restored later
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub redactions: Vec<RedactionRegion>,
+}
+
+pub fn load_presets() -> Vec<Preset> {
+    storage::load_json(PRESETS_KEY).unwrap_or_default()
+}
+
+pub fn save_presets(presets: &[Preset]) {
+    storage::save_json(PRESETS_KEY, &presets);
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::RecorderError;
+use crate::{image, redact, storage};
+
+const AVATAR_KEY: &str = "demrec.camera_avatar";
+
+// Size of one pixelation block in the camera bubble
+const PIXEL_BLOCK_SIZE: f64 = 16.0;
+// Blur radius in the camera bubble
+const BLUR_RADIUS: f64 = 14.0;
+
+/// How the camera is hidden while privacy mode is on. The camera stream keeps
+/// running underneath so switching back is instant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraPrivacy {
+    Pixelate,
+    Blur,
+    Avatar,
+}
+
+impl CameraPrivacy {
+    pub const ALL: [CameraPrivacy; 3] = [CameraPrivacy::Pixelate, CameraPrivacy::Blur, CameraPrivacy::Avatar];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraPrivacy::Pixelate => "Pixelate",
+            CameraPrivacy::Blur => "Blur",
+            CameraPrivacy::Avatar => "Avatar",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|privacy| privacy.label() == label)
+    }
+}
+
+/// Avatar image shown instead of the camera, as (data URL, loaded image)
+pub fn load_avatar() -> Option<(String, web_sys::HtmlImageElement)> {
+    let url = storage::load_json::<String>(AVATAR_KEY)?;
+    let image = image::load_image(&url)?;
+    Some((url, image))
+}
+
+pub fn save_avatar(url: Option<&str>) -> Result<(), RecorderError> {
+    storage::try_save_json(AVATAR_KEY, &url, "the camera avatar")
+}
+
+/// Draw the camera source rectangle into the bubble with the privacy effect
+/// applied. The context is expected to be clipped to the bubble already.
+pub fn draw_private_camera(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    camera_video: &web_sys::HtmlVideoElement,
+    scratch: &web_sys::HtmlCanvasElement,
+    privacy: CameraPrivacy,
+    src: (f64, f64, f64, f64),
+    dest: (f64, f64, f64, f64),
+) {
+    let draw_camera = |ctx: &web_sys::CanvasRenderingContext2d, src: (f64, f64, f64, f64), dest: (f64, f64, f64, f64)| {
+        let _ = ctx.draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            camera_video,
+            src.0,
+            src.1,
+            src.2,
+            src.3,
+            dest.0,
+            dest.1,
+            dest.2,
+            dest.3,
+        );
+    };
+    let bounds = (camera_video.video_width() as f64, camera_video.video_height() as f64);
+
+    match privacy {
+        CameraPrivacy::Blur => redact::draw_blurred(ctx, BLUR_RADIUS, bounds, src, dest, &draw_camera),
+        CameraPrivacy::Pixelate => redact::draw_pixelated(ctx, scratch, PIXEL_BLOCK_SIZE, src, dest, &draw_camera),
+        CameraPrivacy::Avatar => {}
+    }
+}
+
+/// Draw the avatar image, or a placeholder silhouette if none is set
+pub fn draw_avatar(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    avatar: Option<&web_sys::HtmlImageElement>,
+    dest: (f64, f64, f64, f64),
+) {
+    let (x, y, width, height) = dest;
+
+    ctx.set_fill_style_str("#374151");
+    ctx.fill_rect(x, y, width, height);
+
+    match avatar {
+        Some(avatar) => image::draw_cover(ctx, avatar, x, y, width, height),
+        None => {
+            // Head and shoulders
+            let center_x = x + width / 2.0;
+            let unit = width.min(height);
+            ctx.set_fill_style_str("#9ca3af");
+            ctx.begin_path();
+            let _ = ctx.arc(center_x, y + height * 0.4, unit * 0.18, 0.0, std::f64::consts::TAU);
+            ctx.fill();
+            ctx.begin_path();
+            let _ = ctx.ellipse(
+                center_x,
+                y + height * 0.4 + unit * 0.5,
+                unit * 0.32,
+                unit * 0.26,
+                0.0,
+                0.0,
+                std::f64::consts::TAU,
+            );
+            ctx.fill();
+        }
+    }
+}
@@ -0,0 +1,3452 @@
+use dioxus::prelude::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::adjust::{self, CameraAdjustments};
+use crate::animation::Tween;
+use crate::background::{self, BackgroundImage, LetterboxFill, VirtualBackground};
+use crate::capability::Capabilities;
+use crate::chroma::{self, ChromaKey};
+use crate::compositor::{
+    z, CameraLayer, CameraLook, Compositor, CountdownLayer, CropSelectionLayer, CurtainLayer, LayerId, PointerLayer,
+    RedactSelectionLayer, SceneTransitionLayer, ScreenLayer,
+};
+use crate::curtain::{self, Curtain, CURTAIN_FADE_MS};
+use crate::error::{Device, RecorderError};
+use crate::frame::{self, FrameStyle};
+use crate::geometry::selection_rect;
+use crate::layout::{CameraShape, Layout, Placement, LAYOUT_TRANSITION_MS};
+use crate::library::{self, SavedRecording};
+use crate::markers::{self, Marker, MARKER_HOTKEY};
+use crate::media::{InputDevice, Permission};
+use crate::panels::{LibraryPanel, ReadinessPanel, ReviewPanel};
+use crate::privacy::{self, CameraPrivacy};
+use crate::recording::{RecordingClock, RecordingEvent, RecordingState, COUNTDOWN_SECONDS};
+use crate::redact::{RedactStyle, RedactionRegion};
+use crate::review::{self, Review};
+use crate::scene::{self, Scene, SceneTransition, SCENE_TRANSITION_MS};
+use crate::screen::{ScreenSource, ScreenTransform};
+use crate::session::{self, RecordingSession};
+use crate::hotkey::{self, FREEZE_HOTKEY, PRIVACY_HOTKEY};
+use crate::{camera, capability, error, geometry, image, media, preset};
+
+// How long an error toast stays up
+const NOTICE_MS: u32 = 10_000;
+
+// Styles the inline ones rely on, scoped to the recorder so the host page's
+// stylesheet can't break the panel sizes
+const RECORDER_CSS: &str = ".demrec-recorder, .demrec-recorder * { box-sizing: border-box; } .demrec-recorder canvas { display: block; }";
+
+// Settings panel shown on the right-hand side
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Panel {
+    Scenes,
+    Curtains,
+    Screen,
+    Camera,
+}
+
+// Create a canvas that is never attached to the page
+fn create_offscreen_canvas() -> Option<web_sys::HtmlCanvasElement> {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+}
+
+// Constraints asking for the camera only
+fn camera_constraints() -> web_sys::MediaStreamConstraints {
+    let constraints = web_sys::MediaStreamConstraints::new();
+    constraints.set_video(&true.into());
+    constraints.set_audio(&false.into());
+    constraints
+}
+
+// Constraints asking for the microphone only
+fn microphone_constraints() -> web_sys::MediaStreamConstraints {
+    let constraints = web_sys::MediaStreamConstraints::new();
+    constraints.set_audio(&true.into());
+    constraints.set_video(&false.into());
+    constraints
+}
+
+// Size of the browser viewport, which the canvas is sized to
+fn viewport_size() -> (f64, f64) {
+    let Some(window) = web_sys::window() else {
+        return (1280.0, 720.0);
+    };
+    let width = window.inner_width().unwrap_or(1280.into()).as_f64().unwrap_or(1280.0);
+    let height = window.inner_height().unwrap_or(720.into()).as_f64().unwrap_or(720.0);
+    (width, height)
+}
+
+// Save a blob through the browser's downloads
+fn download_blob(blob: &web_sys::Blob, file_name: &str) {
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(blob) else {
+        return;
+    };
+    if let Some(anchor) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|link| link.dyn_into::<web_sys::HtmlAnchorElement>().ok())
+    {
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
+
+// Whether a keyboard event is aimed at a text field and should not trigger shortcuts
+fn is_typing_target(event: &web_sys::KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+        .unwrap_or(false)
+}
+
+/// The full recorder: screen share and camera composited into a canvas, with
+/// the toolbar and settings panels, recording the canvas and microphone.
+///
+/// When a recording stops it is shown for review, to be trimmed and saved to
+/// the in-browser library (or downloaded where the browser can't keep files),
+/// or discarded. If `on_recording_finished` is given, the blob is handed to it
+/// instead, skipping the review and the library.
+#[component]
+pub fn Recorder(
+    /// Called once the countdown has finished and the recorder has started
+    on_recording_started: Option<EventHandler<()>>,
+    /// Called with the recorded video (WebM, or MP4 where WebM can't be recorded) when recording stops.
+    /// Setting it skips the review, so the whole take is handed over, seekable but untrimmed,
+    /// with its markers written into WebM files as chapters
+    on_recording_finished: Option<EventHandler<web_sys::Blob>>,
+    /// Called when something fails, such as a denied permission or a full storage
+    on_error: Option<EventHandler<RecorderError>>,
+) -> Element {
+    let mut position = use_signal(|| (100.0, 100.0));
+    let mut is_dragging = use_signal(|| false);
+    let mut is_resizing = use_signal(|| false);
+    let mut drag_offset = use_signal(|| (0.0, 0.0));
+    let mut camera_video_ref = use_signal(|| None::<web_sys::HtmlVideoElement>);
+    let mut screen_video_ref = use_signal(|| None::<web_sys::HtmlVideoElement>);
+    let mut canvas_ref = use_signal(|| None::<web_sys::HtmlCanvasElement>);
+    let mut size = use_signal(|| (160.0, 120.0)); // Default size
+    let mut resize_start = use_signal(|| (0.0, 0.0));
+    let mut resize_corner = use_signal(|| "");
+    let mut screen_stream = use_signal(|| None::<web_sys::MediaStream>);
+    let mut is_screen_sharing = use_signal(|| false);
+    let mut animation_frame_id = use_signal(|| None::<i32>);
+    let mut cursor_state = use_signal(|| "default"); // "default", "grab", "nwse-resize"
+    let mut recording = use_signal(RecordingState::default);
+    let mut recording_clock = use_signal(RecordingClock::default);
+    let mut recording_thumbnail = use_signal(|| None::<String>); // taken from the canvas on stop
+    let mut recording_markers = use_signal(Vec::<Marker>::new); // dropped with the marker hotkey while recording
+    let mut editing_marker = use_signal(|| None::<usize>); // marker whose label is being typed
+    let mut marker_flash = use_signal(markers::load_marker_flash);
+    let mut is_marker_flashing = use_signal(|| false);
+    let media_recorder = use_signal(|| None::<web_sys::MediaRecorder>);
+    let recorded_chunks = use_signal(Vec::<web_sys::Blob>::new);
+    let mut camera_enabled = use_signal(|| false);
+    let mut camera_stream = use_signal(|| None::<web_sys::MediaStream>);
+    let mut is_crop_mode = use_signal(|| false);
+    let mut crop_start = use_signal(|| (0.0, 0.0));
+    let mut crop_end = use_signal(|| (0.0, 0.0));
+    let mut is_drawing_crop = use_signal(|| false);
+    let mut crop_bounds = use_signal(|| None::<(f64, f64, f64, f64)>); // (x, y, width, height) in screen video coordinates
+    let capabilities = use_signal(Capabilities::detect);
+    // The capability report opens by itself when the browser can't record at all
+    let mut show_capabilities = use_signal(move || !capabilities.peek().can_record());
+    let mut file_handle = use_signal(|| None::<web_sys::FileSystemFileHandle>);
+    let mut file_stream = use_signal(|| None::<web_sys::FileSystemWritableFileStream>);
+    let mut is_pointer_mode = use_signal(|| false);
+    let mut pointer_position = use_signal(|| (0.0, 0.0));
+    let mut camera_zoom = use_signal(|| 1.0); // 1.0 = normal, >1.0 = zoomed in
+    let mut camera_shape = use_signal(CameraShape::default);
+    let pip_video_element = use_signal(|| None::<web_sys::HtmlVideoElement>);
+    let is_pip_active = use_signal(|| false);
+    let mut is_redact_mode = use_signal(|| false);
+    let mut is_drawing_redaction = use_signal(|| false);
+    let mut redact_start = use_signal(|| (0.0, 0.0));
+    let mut redact_end = use_signal(|| (0.0, 0.0));
+    let mut redactions = use_signal(Vec::<RedactionRegion>::new); // in screen video coordinates
+    let mut redact_style = use_signal(|| RedactStyle::Pixelate); // style for newly drawn regions
+    let mut presets = use_signal(preset::load_presets);
+    let mut preset_name = use_signal(String::new);
+    // Offscreen canvas used to pixelate redaction regions
+    let redact_scratch = use_signal(create_offscreen_canvas);
+    let mut is_screen_frozen = use_signal(|| false);
+    // Copy of the screen frame shown while frozen, in screen video resolution
+    let frozen_screen_frame = use_signal(create_offscreen_canvas);
+    let mut curtains = use_signal(curtain::load_curtains);
+    let mut active_curtain = use_signal(|| None::<usize>); // curtain that is up (or fading in)
+    let mut shown_curtain = use_signal(|| None::<usize>); // curtain being drawn, kept while fading out
+    let mut curtain_fade = use_signal(|| Tween::settled(0.0));
+    let mut open_panel = use_signal(|| None::<Panel>);
+    let mut mic_stream = use_signal(|| None::<web_sys::MediaStream>);
+    let mut camera_private = use_signal(|| false); // hide the camera without stopping it
+    let mut camera_privacy = use_signal(|| CameraPrivacy::Pixelate);
+    let mut camera_avatar = use_signal(privacy::load_avatar);
+    // Offscreen canvas used to pixelate the camera
+    let camera_scratch = use_signal(create_offscreen_canvas);
+    let mut chroma_key = use_signal(chroma::load_chroma_key);
+    let mut is_picking_key_color = use_signal(|| false);
+    // Offscreen canvas for per-pixel camera processing
+    let camera_work = use_signal(camera::create_work_canvas);
+    let mut layout = use_signal(|| Layout::Bubble);
+    let mut layout_from = use_signal(|| None::<Placement>); // where the last layout switch started
+    let mut layout_transition = use_signal(|| Tween::settled(1.0));
+    let mut scenes = use_signal(scene::load_scenes);
+    let mut active_scene = use_signal(|| None::<usize>); // last recalled scene
+    // Copy of the frame shown before the last scene recall, faded or slid away
+    let scene_snapshot = use_signal(create_offscreen_canvas);
+    let mut scene_transition = use_signal(|| SceneTransition::Cut);
+    let mut scene_progress = use_signal(|| Tween::settled(1.0));
+    let mut virtual_background = use_signal(background::load_virtual_background);
+    let mut background_images = use_signal(background::load_background_images);
+    // Offscreen canvas used to blur the screen behind the presenter
+    let background_scratch = use_signal(create_offscreen_canvas);
+    let mut camera_device_id = use_signal(|| None::<String>);
+    let mut camera_adjust = use_signal(CameraAdjustments::default); // for the active camera device
+    let mut camera_lut = use_signal(|| None::<adjust::Lut>);
+    let mut lut_error = use_signal(|| None::<String>);
+    let mut adjust_preview_ref = use_signal(|| None::<web_sys::HtmlCanvasElement>);
+    let mut letterbox_fill = use_signal(background::load_letterbox_fill);
+    let mut frame_style = use_signal(frame::load_frame_style);
+    let mut notices = use_signal(Vec::<(u32, RecorderError)>::new); // toasts shown top right, with ids
+    let mut next_notice_id = use_signal(|| 0u32);
+    let mut show_diagnostics = use_signal(|| false);
+    let mut show_readiness = use_signal(|| false); // pre-recording permission and source check
+    let mut camera_permission = use_signal(|| Permission::Unknown);
+    let mut mic_permission = use_signal(|| Permission::Unknown);
+    let mut input_devices = use_signal(Vec::<InputDevice>::new);
+    let mut mic_level = use_signal(|| 0.0); // 0-1, measured while the readiness screen is up
+    // Session the recording in progress is kept on disk as, None when it can't be
+    let mut recording_session = use_signal(|| None::<RecordingSession>);
+    // Bumped on every start, so a cancelled countdown's task knows to give up
+    let mut recording_attempt = use_signal(|| 0u32);
+    // Sessions left over from a crash or a closed tab, offered for recovery
+    let mut unfinished_sessions = use_signal(session::load_abandoned);
+    let mut recovering_session = use_signal(|| None::<String>); // id of the session being put back together
+    let mut show_library = use_signal(|| false);
+    let mut library_recordings = use_signal(library::load_recordings);
+    let mut selected_recordings = use_signal(Vec::<String>::new); // ids ticked in the library for joining
+    let mut is_joining = use_signal(|| false);
+    let mut storage_estimate = use_signal(|| None::<(f64, f64)>); // bytes used and allowed
+    let mut playing_recording = use_signal(|| None::<(String, String)>); // id and object URL in the library player
+    let mut review = use_signal(|| None::<Review>); // take shown for keep or retake after stopping
+    let mut review_position = use_signal(|| 0.0); // playhead in the review player, in milliseconds
+    let mut is_review_playing = use_signal(|| false);
+    let mut is_saving_review = use_signal(|| false);
+
+    // Show a failure with a hint, keep it in the diagnostics log, and tell the embedding app
+    let mut report_error = move |error: RecorderError| {
+        web_sys::console::error_1(&format!("{error}. {}", error.hint()).into());
+        if !error.is_cancellation() {
+            error::log_diagnostic(&error);
+        }
+        if let Some(handler) = on_error {
+            handler.call(error.clone());
+        }
+
+        let id = next_notice_id();
+        next_notice_id.set(id.wrapping_add(1));
+        notices.write().push((id, error));
+        spawn(async move {
+            gloo_timers::future::TimeoutFuture::new(NOTICE_MS).await;
+            notices.write().retain(|(notice_id, _)| *notice_id != id);
+        });
+    };
+
+    let mut report_result = move |result: Result<(), RecorderError>| {
+        if let Err(error) = result {
+            report_error(error);
+        }
+    };
+
+    // Move the recording along, returning false for events its current state doesn't allow
+    let mut send_recording = move |event: RecordingEvent| {
+        let next = recording.peek().next(&event);
+        match next {
+            Some(state) => {
+                let clock = recording_clock.peek().update(&state, js_sys::Date::now());
+                recording_clock.set(clock);
+                recording.set(state);
+                true
+            }
+            None => false,
+        }
+    };
+
+    let mut fail_recording = move |error: RecorderError| {
+        send_recording(RecordingEvent::Fail(error.to_string()));
+        report_error(error);
+    };
+
+    // Keep a recording in the library and show it there, downloading it instead
+    // where the browser can't keep files
+    let keep_recording = move |blob: web_sys::Blob, mime_type: String, duration_ms: f64, thumbnail: Option<String>, markers: Vec<Marker>| async move {
+        if capabilities.peek().opfs {
+            match library::add(blob.clone(), &mime_type, None, duration_ms, thumbnail, markers).await {
+                Ok(_) => {
+                    library_recordings.set(library::load_recordings());
+                    show_library.set(true);
+                    return;
+                }
+                Err(error) => report_error(error),
+            }
+        }
+        let file_name = library::default_name(js_sys::Date::now());
+        download_blob(&blob, &format!("{file_name}.{}", capability::file_extension(&mime_type)));
+    };
+
+    // Show a finished take in the review player, decoding its waveform meanwhile
+    let mut open_review = move |blob: web_sys::Blob, mime_type: String, duration_ms: f64, thumbnail: Option<String>, markers: Vec<Marker>| {
+        let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+            return false;
+        };
+        review_position.set(0.0);
+        is_review_playing.set(false);
+        review.set(Some(Review {
+            blob: blob.clone(),
+            url: url.clone(),
+            mime_type,
+            duration_ms,
+            thumbnail,
+            waveform: Vec::new(),
+            trim: None,
+            cuts: Vec::new(),
+            cut_start: None,
+            markers,
+        }));
+        spawn(async move {
+            let waveform = review::decode_waveform(&blob, review::WAVEFORM_BARS).await.unwrap_or_default();
+            if let Some(current) = review.write().as_mut().filter(|current| current.url == url) {
+                current.waveform = waveform;
+            }
+        });
+        true
+    };
+
+    let mut close_review = move || {
+        if let Some(closed) = review.take() {
+            let _ = web_sys::Url::revoke_object_url(&closed.url);
+        }
+        is_review_playing.set(false);
+    };
+
+    // The recording was saved, so its copy on disk can go
+    let mut finish_session = move || {
+        if let Some(saved) = recording_session.take() {
+            spawn(async move { report_result(session::discard(&saved.id).await) });
+        }
+    };
+
+    // Stop the recording, or cancel the countdown; the recorder's stop handler saves it
+    let mut stop_recording = move || {
+        let was_capturing = recording.peek().is_capturing();
+        if !send_recording(RecordingEvent::Stop) || !was_capturing {
+            return;
+        }
+        recording_thumbnail.set(canvas_ref().and_then(|canvas| image::canvas_thumbnail(&canvas, library::THUMBNAIL_SIZE)));
+        if let Some(recorder) = media_recorder() {
+            recorder.stop().unwrap_or(());
+        }
+
+        // Close the file stream when writing through the File System Access API
+        if capabilities.read().file_system_access {
+            if let Some(stream) = file_stream() {
+                spawn(async move {
+                    // Use js_sys to call close method
+                    if let Ok(close_method) = js_sys::Reflect::get(&stream, &"close".into()) {
+                        if let Ok(func) = close_method.dyn_into::<js_sys::Function>() {
+                            if let Ok(promise_js) = js_sys::Reflect::apply(&func, &stream, &js_sys::Array::new()) {
+                                if let Ok(promise) = promise_js.dyn_into::<js_sys::Promise>() {
+                                    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+                                }
+                            }
+                        }
+                    }
+                });
+                file_stream.set(None);
+                file_handle.set(None);
+            }
+        }
+    };
+
+    let start_camera = move || {
+        spawn(async move {
+            match media::user_media(Device::Camera, &camera_constraints()).await {
+                Ok(media_stream) => {
+                    camera_stream.set(Some(media_stream.clone()));
+
+                    if let Some(video_elem) = camera_video_ref() {
+                        video_elem.set_src_object(Some(&media_stream));
+                        let _ = video_elem.play();
+                    }
+
+                    camera_enabled.set(true);
+                }
+                Err(error) => report_error(error),
+            }
+        });
+    };
+
+    let mut stop_camera = move || {
+        if let Some(stream) = camera_stream() {
+            let tracks = stream.get_tracks();
+            for i in 0..tracks.length() {
+                let track = tracks.get(i);
+                if let Ok(media_track) = track.dyn_into::<web_sys::MediaStreamTrack>() {
+                    media_track.stop();
+                }
+            }
+        }
+        camera_stream.set(None);
+
+        // Clear video element
+        if let Some(video_elem) = camera_video_ref() {
+            video_elem.set_src_object(None);
+        }
+
+        camera_enabled.set(false);
+    };
+
+    let start_screen_share = move || {
+        spawn(async move {
+            match media::display_media().await {
+                Ok(media_stream) => {
+                    // Set the stream to screen video element
+                    if let Some(video_elem) = screen_video_ref() {
+                        video_elem.set_src_object(Some(&media_stream));
+                        let _ = video_elem.play();
+                    }
+                    screen_stream.set(Some(media_stream.clone()));
+                    is_screen_sharing.set(true);
+
+                    // Reset screen share crop and freeze when starting
+                    crop_bounds.set(None);
+                    is_screen_frozen.set(false);
+                }
+                Err(error) => report_error(error),
+            }
+        });
+    };
+
+    // Ask for the microphone up front so the prompt doesn't eat into a take
+    let start_microphone = move || {
+        spawn(async move {
+            match media::user_media(Device::Microphone, &microphone_constraints()).await {
+                Ok(stream) => mic_stream.set(Some(stream)),
+                Err(error) => report_error(error),
+            }
+        });
+    };
+
+    // Whether there is something to record and a microphone to record it with
+    let sources_ready = move || {
+        let mic_live = mic_stream().is_some_and(|stream| media::is_live(&stream));
+        mic_live && (is_screen_sharing() || camera_enabled())
+    };
+
+    // Count down and start recording the canvas and microphone
+    let mut start_recording = move || {
+        if !send_recording(RecordingEvent::Start) {
+            return;
+        }
+        recording_markers.set(Vec::new());
+        editing_marker.set(None);
+        let attempt = *recording_attempt.peek() + 1;
+        recording_attempt.set(attempt);
+        let is_current = move || *recording_attempt.peek() == attempt;
+        let mut recorded_chunks_clone = recorded_chunks;
+        let mut media_recorder_clone = media_recorder;
+        let canvas_ref_clone = canvas_ref;
+        let mut mic_stream_clone = mic_stream;
+
+        spawn(async move {
+            // Count down, giving up if the countdown is cancelled or started again
+            for _ in 0..COUNTDOWN_SECONDS {
+                gloo_timers::future::TimeoutFuture::new(1000).await;
+                if !is_current() || !send_recording(RecordingEvent::Tick) {
+                    return;
+                }
+            }
+
+            let Some(canvas) = canvas_ref_clone() else {
+                fail_recording(RecorderError::RecordingFailed("The canvas isn't ready yet".into()));
+                return;
+            };
+            let Ok(canvas_stream) = canvas.capture_stream() else {
+                fail_recording(RecorderError::Unsupported("Recording the canvas".into()));
+                return;
+            };
+
+            // Use the microphone checked on the readiness screen, asking for it only if it went away
+            let live_mic = mic_stream_clone.peek().clone().filter(media::is_live);
+            let audio_stream = match live_mic {
+                Some(stream) => stream,
+                None => match media::user_media(Device::Microphone, &microphone_constraints()).await {
+                    Ok(stream) => {
+                        // Keep the mic stream so curtains can mute it
+                        mic_stream_clone.set(Some(stream.clone()));
+                        stream
+                    }
+                    Err(error) => {
+                        if is_current() {
+                            fail_recording(error);
+                        }
+                        return;
+                    }
+                },
+            };
+            if !is_current() {
+                return;
+            }
+
+            // Combine canvas and audio streams
+            let audio_tracks = audio_stream.get_audio_tracks();
+            for i in 0..audio_tracks.length() {
+                let track = audio_tracks.get(i);
+                if let Ok(audio_track) = track.dyn_into::<web_sys::MediaStreamTrack>() {
+                    canvas_stream.add_track(&audio_track);
+                }
+            }
+
+            // Create MediaRecorder in the best format this browser can encode (WebM, or MP4 on Safari)
+            let Some(mime_type) = capabilities.peek().recording_mime_type().map(str::to_string) else {
+                fail_recording(RecorderError::UnsupportedCodec);
+                return;
+            };
+            let options = web_sys::MediaRecorderOptions::new();
+            options.set_mime_type(&mime_type);
+            let Ok(recorder) = web_sys::MediaRecorder::new_with_media_stream_and_media_recorder_options(&canvas_stream, &options) else {
+                fail_recording(RecorderError::UnsupportedCodec);
+                return;
+            };
+
+            // Clear previous recordings
+            recorded_chunks_clone.set(Vec::new());
+
+            // Keep the recording on disk as it goes, so a crash doesn't lose it
+            let mut started_session = None;
+            if capabilities.peek().opfs {
+                match session::begin(&mime_type).await {
+                    Ok(started) => started_session = Some(started),
+                    Err(error) => report_error(error),
+                }
+            }
+
+            // Set up data available handler
+            let mut chunks_clone2 = recorded_chunks_clone;
+            let mut next_chunk = 0u32;
+            let data_handler = Closure::wrap(Box::new(move |event: web_sys::BlobEvent| {
+                if let Some(data) = event.data() {
+                    let mut current_chunks = chunks_clone2();
+                    current_chunks.push(data.clone());
+                    chunks_clone2.set(current_chunks);
+
+                    let index = next_chunk;
+                    next_chunk += 1;
+                    if let Some(current) = recording_session.peek().clone() {
+                        spawn(async move {
+                            if let Err(error) = session::save_chunk(&current.id, index, &data).await {
+                                // Stop writing after the first failure rather than failing every second
+                                if recording_session.peek().as_ref() == Some(&current) {
+                                    recording_session.set(None);
+                                    report_error(error);
+                                }
+                            }
+                        });
+                    }
+                }
+            }) as Box<dyn FnMut(web_sys::BlobEvent)>);
+
+            recorder.set_ondataavailable(Some(data_handler.as_ref().unchecked_ref()));
+            data_handler.forget();
+
+            // Set up stop handler
+            let recorded_chunks_clone3 = recorded_chunks_clone;
+            let stop_handler = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                // A recorder stopped after a cancel or failure has nothing to save
+                if *recording.peek() != RecordingState::Finalizing {
+                    return;
+                }
+
+                // Create and download blob when recording stops
+                let chunks = recorded_chunks_clone3();
+                if chunks.is_empty() {
+                    fail_recording(RecorderError::RecordingFailed("The recording is empty".into()));
+                    return;
+                }
+
+                let blob_parts = js_sys::Array::new();
+                for chunk in chunks {
+                    blob_parts.push(&chunk);
+                }
+
+                let blob_options = web_sys::BlobPropertyBag::new();
+                blob_options.set_type(capability::container_type(&mime_type));
+
+                let Ok(blob) = web_sys::Blob::new_with_blob_sequence_and_options(&blob_parts, &blob_options) else {
+                    fail_recording(RecorderError::RecordingFailed("Could not assemble the recording".into()));
+                    return;
+                };
+
+                // Hand the recording to the embedding app if it wants it, seekable but unreviewed
+                if let Some(handler) = on_recording_finished {
+                    let mime_type = mime_type.clone();
+                    let markers = recording_markers.peek().clone();
+                    spawn(async move {
+                        let (blob, _) = library::make_seekable(blob, &mime_type).await;
+                        handler.call(library::add_chapters(blob, &mime_type, &markers).await);
+                        send_recording(RecordingEvent::Finished);
+                        finish_session();
+                    });
+                    return;
+                }
+
+                // Look it over before keeping it; saved straight away if it can't be played back
+                let duration_ms = recording_clock.peek().elapsed_ms(js_sys::Date::now());
+                let thumbnail = recording_thumbnail.take();
+                let markers = recording_markers.peek().clone();
+                let mime_type = mime_type.clone();
+                spawn(async move {
+                    let (blob, measured) = library::make_seekable(blob, &mime_type).await;
+                    let duration_ms = measured.unwrap_or(duration_ms);
+                    if open_review(blob.clone(), mime_type.clone(), duration_ms, thumbnail.clone(), markers.clone()) {
+                        send_recording(RecordingEvent::Review);
+                    } else {
+                        keep_recording(blob, mime_type, duration_ms, thumbnail, markers).await;
+                        finish_session();
+                        send_recording(RecordingEvent::Finished);
+                    }
+                });
+            }) as Box<dyn FnMut(web_sys::Event)>);
+
+            recorder.set_onstop(Some(stop_handler.as_ref().unchecked_ref()));
+            stop_handler.forget();
+
+            let error_handler = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                fail_recording(RecorderError::RecordingFailed("The recorder stopped unexpectedly".into()));
+            }) as Box<dyn FnMut(web_sys::Event)>);
+            recorder.set_onerror(Some(error_handler.as_ref().unchecked_ref()));
+            error_handler.forget();
+
+            // Start recording, unless the countdown was cancelled or started again meanwhile
+            if !is_current() || *recording.peek() != (RecordingState::Countdown { remaining: 0 }) {
+                if let Some(started) = started_session {
+                    spawn(async move { report_result(session::discard(&started.id).await) });
+                }
+                return;
+            }
+            recording_session.set(started_session);
+            if recorder.start_with_time_slice(session::CHUNK_MS).is_err() {
+                finish_session();
+                fail_recording(RecorderError::RecordingFailed("The recorder failed to start".into()));
+                return;
+            }
+            media_recorder_clone.set(Some(recorder));
+            send_recording(RecordingEvent::Started);
+            if let Some(handler) = on_recording_started {
+                handler.call(());
+            }
+        });
+    };
+
+    // Keep the session's copy of the markers current, so they are recovered with the take
+    let mut save_session_markers = move || {
+        if let Some(current) = recording_session.peek().as_ref() {
+            report_result(session::save_markers(&current.id, &recording_markers.peek()));
+        }
+    };
+
+    // Drop a marker at this point of the take and offer to name it
+    let mut drop_marker = move || {
+        if !recording.peek().is_capturing() {
+            return;
+        }
+        let time_ms = recording_clock.peek().elapsed_ms(js_sys::Date::now());
+        recording_markers.write().push(Marker::new(time_ms));
+        editing_marker.set(Some(recording_markers.peek().len() - 1));
+        save_session_markers();
+        // On screen only; the recording is drawn without it
+        if *marker_flash.peek() {
+            is_marker_flashing.set(true);
+            spawn(async move {
+                gloo_timers::future::TimeoutFuture::new(markers::FLASH_MS).await;
+                is_marker_flashing.set(false);
+            });
+        }
+    };
+
+    // Keep the take, cut down to the trim range and without the cuts
+    let mut save_review = move || {
+        let Some(current) = review.peek().clone() else {
+            return;
+        };
+        is_saving_review.set(true);
+        spawn(async move {
+            match review::trimmed(&current).await {
+                Ok((blob, duration_ms, markers)) => {
+                    keep_recording(blob, current.mime_type.clone(), duration_ms, current.thumbnail.clone(), markers).await;
+                    close_review();
+                    finish_session();
+                    send_recording(RecordingEvent::Finished);
+                }
+                Err(error) => report_error(error),
+            }
+            is_saving_review.set(false);
+        });
+    };
+
+    // Throw the take away without saving it
+    let mut discard_review = move || {
+        close_review();
+        finish_session();
+        send_recording(RecordingEvent::Discard);
+    };
+
+    // Throw the take away and count down again; the scene and sources are as they were
+    let mut retake = move || {
+        discard_review();
+        if sources_ready() {
+            start_recording();
+        } else {
+            show_readiness.set(true);
+        }
+    };
+
+    // Where the screen and camera are right now, part way between layouts while switching
+    let layout_placement = move |viewport_width: f64, viewport_height: f64| {
+        let (pos, cam_size) = (position(), size());
+        let target = layout().placement(viewport_width, viewport_height, (pos.0, pos.1, cam_size.0, cam_size.1), camera_shape());
+        match layout_from() {
+            Some(from) => from.towards(&target, layout_transition().value(js_sys::Date::now())),
+            None => target,
+        }
+    };
+
+    // Animate to another layout, starting from wherever things are now
+    let mut switch_layout = move |next: Layout| {
+        if next == layout() {
+            return;
+        }
+        let (viewport_width, viewport_height) = viewport_size();
+        let now = js_sys::Date::now();
+        layout_from.set(Some(layout_placement(viewport_width, viewport_height)));
+        layout_transition.set(Tween::settled(0.0).towards(1.0, now, LAYOUT_TRANSITION_MS));
+        layout.set(next);
+    };
+
+    // Capture everything that makes up the current composition
+    let current_scene = move |name: String, key: String, transition: SceneTransition| Scene {
+        name,
+        key,
+        transition,
+        layout: layout(),
+        camera_enabled: Some(camera_enabled()),
+        camera_private: Some(camera_private()),
+        camera_shape: camera_shape(),
+        camera_position: position(),
+        camera_size: size(),
+        camera_zoom: camera_zoom(),
+        crop: crop_bounds(),
+        redactions: redactions(),
+        frame_style: frame_style(),
+        letterbox_fill: letterbox_fill(),
+        virtual_background: virtual_background(),
+    };
+
+    // Recall a saved scene, keeping a copy of the current frame for the transition
+    let mut recall_scene = move |index: usize| {
+        let Some(recalled) = scenes.read().get(index).cloned() else {
+            return;
+        };
+
+        if recalled.transition != SceneTransition::Cut {
+            if let (Some(canvas), Some(snapshot)) = (canvas_ref(), scene_snapshot()) {
+                snapshot.set_width(canvas.width());
+                snapshot.set_height(canvas.height());
+                if let Ok(Some(context)) = snapshot.get_context("2d") {
+                    if let Ok(snapshot_ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() {
+                        let _ = snapshot_ctx.draw_image_with_html_canvas_element(&canvas, 0.0, 0.0);
+                    }
+                }
+            }
+        }
+        scene_transition.set(recalled.transition);
+        scene_progress.set(Tween::settled(0.0).towards(1.0, js_sys::Date::now(), SCENE_TRANSITION_MS));
+
+        // The scene transition replaces the layout animation
+        layout.set(recalled.layout);
+        layout_from.set(None);
+        match recalled.camera_enabled {
+            Some(true) if !camera_enabled() => start_camera(),
+            Some(false) if camera_enabled() => stop_camera(),
+            _ => {}
+        }
+        if let Some(private) = recalled.camera_private {
+            camera_private.set(private);
+        }
+        camera_shape.set(recalled.camera_shape);
+        position.set(recalled.camera_position);
+        size.set(recalled.camera_size);
+        camera_zoom.set(recalled.camera_zoom);
+        crop_bounds.set(recalled.crop);
+        redactions.set(recalled.redactions);
+        frame::save_frame_style(&recalled.frame_style);
+        frame_style.set(recalled.frame_style);
+        background::save_letterbox_fill(&recalled.letterbox_fill);
+        letterbox_fill.set(recalled.letterbox_fill);
+        background::save_virtual_background(&recalled.virtual_background);
+        virtual_background.set(recalled.virtual_background);
+        active_scene.set(Some(index));
+    };
+
+    // Current placement of the screen share on the canvas (None if nothing is shown)
+    let screen_transform = move || {
+        let screen_video = screen_video_ref()?;
+        if !is_screen_sharing() || screen_video.ready_state() < 2 {
+            return None;
+        }
+
+        let video_width = screen_video.video_width() as f64;
+        let video_height = screen_video.video_height() as f64;
+        if video_width <= 0.0 || video_height <= 0.0 {
+            return None;
+        }
+
+        let (viewport_width, viewport_height) = viewport_size();
+        let area = layout_placement(viewport_width, viewport_height).screen;
+
+        // Apply crop if set, fitting into the frame when it is styled
+        let src = crop_bounds().unwrap_or((0.0, 0.0, video_width, video_height));
+        ScreenTransform::fit(src, frame_style().screen_area(area))
+    };
+
+    // The screen pixels to draw: the frozen frame while frozen, otherwise the live share
+    let screen_source = move || {
+        if is_screen_frozen() {
+            frozen_screen_frame().map(ScreenSource::Frozen)
+        } else {
+            screen_video_ref().map(ScreenSource::Live)
+        }
+    };
+
+    // Freeze the screen share on its current frame, or resume it
+    let mut toggle_screen_freeze = move || {
+        if is_screen_frozen() {
+            is_screen_frozen.set(false);
+            return;
+        }
+
+        if let (Some(screen_video), Some(frame)) = (screen_video_ref(), frozen_screen_frame()) {
+            if !is_screen_sharing() || screen_video.ready_state() < 2 {
+                return;
+            }
+            frame.set_width(screen_video.video_width());
+            frame.set_height(screen_video.video_height());
+
+            if let Ok(Some(context)) = frame.get_context("2d") {
+                if let Ok(frame_ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() {
+                    let _ = frame_ctx.draw_image_with_html_video_element(&screen_video, 0.0, 0.0);
+                    is_screen_frozen.set(true);
+                }
+            }
+        }
+    };
+
+    // Fade a curtain in, or out if it is already up
+    let mut toggle_curtain = move |index: usize| {
+        let now = js_sys::Date::now();
+        if active_curtain() == Some(index) {
+            active_curtain.set(None);
+            curtain_fade.set(curtain_fade().towards(0.0, now, CURTAIN_FADE_MS));
+        } else {
+            active_curtain.set(Some(index));
+            shown_curtain.set(Some(index));
+            curtain_fade.set(curtain_fade().towards(1.0, now, CURTAIN_FADE_MS));
+        }
+    };
+
+    // Mute the microphone while a curtain that asks for it is up
+    use_effect(move || {
+        let muted = active_curtain()
+            .and_then(|index| curtains.read().get(index).map(|curtain| curtain.mute_mic))
+            .unwrap_or(false);
+
+        if let Some(stream) = mic_stream() {
+            let tracks = stream.get_audio_tracks();
+            for i in 0..tracks.length() {
+                if let Ok(track) = tracks.get(i).dyn_into::<web_sys::MediaStreamTrack>() {
+                    track.set_enabled(!muted);
+                }
+            }
+        }
+    });
+
+    // Keep the session on disk marked as live while it is recorded or reviewed,
+    // so other tabs don't take it for an abandoned one
+    use_effect(move || {
+        let Some(live) = recording_session() else {
+            return;
+        };
+        spawn(async move {
+            while recording_session.peek().as_ref().is_some_and(|current| current.id == live.id) {
+                // A failed write shows up on the next chunk
+                let _ = session::touch(&live.id);
+                gloo_timers::future::TimeoutFuture::new(session::HEARTBEAT_MS).await;
+            }
+        });
+    });
+
+    // Clear away abandoned sessions with nothing in them, such as a recording that failed to start
+    use_effect(move || {
+        let empty: Vec<_> = unfinished_sessions
+            .peek()
+            .iter()
+            .filter(|unfinished| unfinished.chunk_count == 0)
+            .map(|unfinished| unfinished.id.clone())
+            .collect();
+        if empty.is_empty() {
+            return;
+        }
+        unfinished_sessions.write().retain(|unfinished| unfinished.chunk_count > 0);
+        spawn(async move {
+            for id in empty {
+                report_result(session::discard(&id).await);
+            }
+        });
+    });
+
+    // Put an unfinished recording back together and save it like a finished one
+    let mut recover_session = move |unfinished: RecordingSession| {
+        recovering_session.set(Some(unfinished.id.clone()));
+        spawn(async move {
+            match session::recover(&unfinished).await {
+                Ok((blob, duration_ms)) => {
+                    match on_recording_finished {
+                        Some(handler) => {
+                            handler.call(library::add_chapters(blob, &unfinished.mime_type, &unfinished.markers).await)
+                        }
+                        None => {
+                            keep_recording(blob, unfinished.mime_type.clone(), duration_ms, None, unfinished.markers.clone()).await
+                        }
+                    }
+                    unfinished_sessions.write().retain(|other| other.id != unfinished.id);
+                    report_result(session::discard(&unfinished.id).await);
+                }
+                Err(error) => report_error(error),
+            }
+            recovering_session.set(None);
+        });
+    };
+
+    let mut discard_session = move |id: String| {
+        unfinished_sessions.write().retain(|other| other.id != id);
+        spawn(async move { report_result(session::discard(&id).await) });
+    };
+
+    // Look up how much browser storage is used whenever the library opens or changes
+    use_effect(move || {
+        let _recordings = library_recordings.read();
+        if !show_library() {
+            return;
+        }
+        spawn(async move { storage_estimate.set(library::storage_estimate().await) });
+    });
+
+    let mut close_player = move || {
+        if let Some((_, url)) = playing_recording.take() {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        }
+    };
+
+    let play_recording = move |saved: SavedRecording| {
+        close_player();
+        spawn(async move {
+            match library::file(&saved).await {
+                Ok(blob) => {
+                    if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
+                        playing_recording.set(Some((saved.id, url)));
+                    }
+                }
+                Err(error) => report_error(error),
+            }
+        });
+    };
+
+    let download_recording = move |saved: SavedRecording| {
+        spawn(async move {
+            match library::file(&saved).await {
+                Ok(blob) => download_blob(&blob, &saved.file_name()),
+                Err(error) => report_error(error),
+            }
+        });
+    };
+
+    // Markers as a chapters file: WebVTT, or timestamps for a YouTube description
+    let download_chapters = move |saved: SavedRecording, youtube: bool| {
+        let (text, extension, mime_type) = if youtube {
+            (markers::to_youtube(&saved.markers), "txt", "text/plain")
+        } else {
+            (markers::to_webvtt(&saved.markers, saved.duration_ms), "vtt", "text/vtt")
+        };
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime_type);
+        let parts = js_sys::Array::of1(&text.into());
+        if let Ok(blob) = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options) {
+            download_blob(&blob, &format!("{} chapters.{extension}", saved.name));
+        }
+    };
+
+    let delete_recording = move |saved: SavedRecording| {
+        let confirmed = web_sys::window()
+            .and_then(|window| window.confirm_with_message(&format!("Delete \"{}\"?", saved.name)).ok())
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        if playing_recording.peek().as_ref().is_some_and(|(id, _)| *id == saved.id) {
+            close_player();
+        }
+        selected_recordings.write().retain(|id| *id != saved.id);
+        spawn(async move {
+            report_result(library::delete(&saved.id).await);
+            library_recordings.set(library::load_recordings());
+        });
+    };
+
+    // Join the ticked recordings into a new one at the top of the library
+    let mut join_recordings = move || {
+        let selected = selected_recordings.peek().clone();
+        let recordings: Vec<SavedRecording> = library_recordings
+            .peek()
+            .iter()
+            .filter(|saved| selected.contains(&saved.id))
+            .cloned()
+            .collect();
+        is_joining.set(true);
+        spawn(async move {
+            match library::join(&recordings).await {
+                Ok(_) => {
+                    selected_recordings.set(Vec::new());
+                    library_recordings.set(library::load_recordings());
+                }
+                Err(error) => report_error(error),
+            }
+            is_joining.set(false);
+        });
+    };
+
+    // Look up permissions and devices while the readiness screen is up, again whenever a source starts
+    use_effect(move || {
+        if !show_readiness() {
+            return;
+        }
+        let _sources = (camera_stream.read(), mic_stream.read(), screen_stream.read());
+        spawn(async move {
+            camera_permission.set(media::query_permission(Device::Camera).await);
+            mic_permission.set(media::query_permission(Device::Microphone).await);
+            input_devices.set(media::list_input_devices().await);
+        });
+    });
+
+    // Show the live sources in the readiness screen's previews
+    use_effect(move || {
+        if !show_readiness() {
+            return;
+        }
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return;
+        };
+        for (id, stream) in [("readiness-camera", camera_stream()), ("readiness-screen", screen_stream())] {
+            if let Some(video) = document
+                .get_element_by_id(id)
+                .and_then(|element| element.dyn_into::<web_sys::HtmlVideoElement>().ok())
+            {
+                video.set_src_object(stream.as_ref());
+                let _ = video.play();
+            }
+        }
+    });
+
+    // Drive the microphone level meter while the readiness screen is up
+    use_effect(move || {
+        let (true, Some(stream)) = (show_readiness(), mic_stream()) else {
+            return;
+        };
+        spawn(async move {
+            let Some(meter) = media::LevelMeter::new(&stream) else {
+                return;
+            };
+            while *show_readiness.peek() && mic_stream.peek().as_ref() == Some(&stream) {
+                mic_level.set(meter.level());
+                gloo_timers::future::TimeoutFuture::new(100).await;
+            }
+            meter.close();
+            mic_level.set(0.0);
+        });
+    });
+
+    // Load the color adjustments saved for the active camera device
+    use_effect(move || {
+        let device_id = camera_stream()
+            .and_then(|stream| stream.get_video_tracks().get(0).dyn_into::<web_sys::MediaStreamTrack>().ok())
+            .and_then(|track| track.get_settings().get_device_id());
+
+        if let Some(device_id) = device_id {
+            let adjustments = adjust::load_adjustments(&device_id);
+            camera_lut.set(adjustments.lut_name.as_deref().and_then(adjust::load_lut));
+            camera_adjust.set(adjustments);
+            camera_device_id.set(Some(device_id));
+        }
+    });
+
+    let save_camera_adjust = move || {
+        let device_id = camera_device_id().unwrap_or_else(|| "default".to_string());
+        adjust::save_adjustments(&device_id, &camera_adjust());
+    };
+
+    // Draw the before/after preview in the camera panel: original on the left, adjusted on the right
+    let draw_adjust_preview = move |camera_video: &web_sys::HtmlVideoElement| {
+        let (Some(preview), Some(work)) = (adjust_preview_ref(), camera_work()) else {
+            return;
+        };
+        let Ok(Some(context)) = preview.get_context("2d") else {
+            return;
+        };
+        let Ok(preview_ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() else {
+            return;
+        };
+
+        let width = preview.width() as f64;
+        let height = preview.height() as f64;
+        let video_width = camera_video.video_width() as f64;
+        let video_height = camera_video.video_height() as f64;
+        if video_width <= 0.0 || video_height <= 0.0 {
+            return;
+        }
+        let src = geometry::camera_source_rect(video_width, video_height, camera_zoom(), width / height);
+
+        preview_ctx.save();
+        preview_ctx.clear_rect(0.0, 0.0, width, height);
+
+        // Mirrored like the bubble
+        let _ = preview_ctx.translate(width, 0.0);
+        let _ = preview_ctx.scale(-1.0, 1.0);
+        let _ = preview_ctx.draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            camera_video, src.0, src.1, src.2, src.3, 0.0, 0.0, width, height,
+        );
+
+        // Adjusted half (the right half on screen is the left half once mirrored)
+        preview_ctx.begin_path();
+        preview_ctx.rect(0.0, 0.0, width / 2.0, height);
+        preview_ctx.clip();
+        let adjustments = camera_adjust();
+        let lut = camera_lut.read();
+        camera::draw_processed(
+            &preview_ctx,
+            camera_video,
+            &work,
+            None,
+            Some((&adjustments, lut.as_ref())),
+            src,
+            (0.0, 0.0, width, height),
+        );
+        preview_ctx.restore();
+
+        // Divider and labels
+        preview_ctx.set_fill_style_str("white");
+        preview_ctx.fill_rect(width / 2.0 - 1.0, 0.0, 2.0, height);
+        preview_ctx.set_font("bold 11px monospace");
+        preview_ctx.set_text_baseline("top");
+        preview_ctx.set_text_align("left");
+        let _ = preview_ctx.fill_text("Before", 6.0, 6.0);
+        preview_ctx.set_text_align("right");
+        let _ = preview_ctx.fill_text("After", width - 6.0, 6.0);
+    };
+
+    // Build the layer stack for the current state and hand it to `use_layers`
+    let with_compositor = move |viewport_width: f64, viewport_height: f64, use_layers: &mut dyn FnMut(&Compositor)| {
+        let placement = layout_placement(viewport_width, viewport_height);
+        let camera_behind = layout().camera_behind();
+        let screen = screen_source().zip(screen_transform());
+        let redactions = redactions.read();
+        let background_images = background_images.read();
+        let letterbox_fill = letterbox_fill.read();
+        let virtual_background = virtual_background.read();
+        let camera_avatar = camera_avatar.read();
+        let camera_adjust = camera_adjust.read();
+        let camera_lut = camera_lut.read();
+        let curtains = curtains.read();
+        let now = js_sys::Date::now();
+
+        let mut compositor = Compositor::default();
+        compositor.push(ScreenLayer {
+            screen: screen.clone(),
+            opacity: placement.screen_opacity,
+            z_order: if camera_behind { z::ABOVE } else { z::BELOW },
+            fill_background: !camera_behind,
+            frame_style: frame_style(),
+            letterbox_fill: &letterbox_fill,
+            background_images: &background_images,
+            redactions: &redactions,
+            background_scratch: background_scratch(),
+            redact_scratch: redact_scratch(),
+        });
+        compositor.push(CameraLayer {
+            video: camera_video_ref().filter(|video| camera_enabled() && video.ready_state() >= 2),
+            rect: placement.camera,
+            corner_radius: placement.camera_radius,
+            opacity: placement.camera_opacity,
+            z_order: if camera_behind { z::BELOW } else { z::ABOVE },
+            interactive: layout() == Layout::Bubble,
+            look: CameraLook {
+                zoom: camera_zoom(),
+                privacy: camera_private().then_some(camera_privacy()),
+                avatar: camera_avatar.as_ref().map(|(_, avatar)| avatar),
+                chroma_key: chroma_key(),
+                adjustments: &camera_adjust,
+                lut: camera_lut.as_ref(),
+                scratch: camera_scratch(),
+                work: camera_work(),
+            },
+            virtual_background: camera_behind.then_some(&*virtual_background),
+            background_images: &background_images,
+            screen,
+            redactions: &redactions,
+            background_scratch: background_scratch(),
+            redact_scratch: redact_scratch(),
+        });
+        compositor.push(SceneTransitionLayer {
+            snapshot: scene_snapshot(),
+            transition: scene_transition(),
+            progress: scene_progress().value(now),
+        });
+        compositor.push(CropSelectionLayer {
+            selection: (is_crop_mode() && is_drawing_crop()).then(|| (crop_start(), crop_end())),
+        });
+        compositor.push(RedactSelectionLayer {
+            selection: (is_redact_mode() && is_drawing_redaction()).then(|| (redact_start(), redact_end())),
+        });
+        compositor.push(PointerLayer {
+            position: is_pointer_mode().then_some(pointer_position()),
+        });
+        compositor.push(CurtainLayer {
+            curtain: shown_curtain().and_then(|index| curtains.get(index)),
+            opacity: curtain_fade().value(now),
+        });
+        compositor.push(CountdownLayer {
+            value: recording().countdown(),
+        });
+
+        use_layers(&compositor);
+    };
+
+    // Topmost layer taking mouse interaction at a canvas point
+    let layer_at = move |x: f64, y: f64| {
+        let (viewport_width, viewport_height) = viewport_size();
+        let mut hit = None;
+        with_compositor(viewport_width, viewport_height, &mut |compositor| hit = compositor.hit_test(x, y));
+        hit
+    };
+
+    // Function to update cursor based on mouse position
+    let mut update_cursor = move |mouse_x: f64, mouse_y: f64| {
+        if is_crop_mode() || is_redact_mode() || is_picking_key_color() {
+            cursor_state.set("crosshair");
+        } else if is_pointer_mode() {
+            cursor_state.set("none"); // Hide cursor when pointer tool is active
+            pointer_position.set((mouse_x, mouse_y));
+        } else {
+            let pos = position();
+            let cam_size = size();
+
+            // Check if mouse is over the camera bubble
+            if layer_at(mouse_x, mouse_y) == Some(LayerId::Camera) {
+                // Check if mouse is in resize corner (20px from bottom-right)
+                let corner_size = 20.0;
+                let rel_x = mouse_x - pos.0;
+                let rel_y = mouse_y - pos.1;
+                let near_right = rel_x > cam_size.0 - corner_size;
+                let near_bottom = rel_y > cam_size.1 - corner_size;
+
+                if near_right && near_bottom {
+                    cursor_state.set("nwse-resize");
+                } else {
+                    cursor_state.set("grab");
+                }
+            } else {
+                cursor_state.set("default");
+            }
+        }
+    };
+
+    // Rendering loop for canvas
+    let render_loop = move || {
+        if let (Some(canvas), Some(camera_video)) = (canvas_ref(), camera_video_ref()) {
+            if let Ok(Some(context)) = canvas.get_context("2d") {
+                if let Ok(ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() {
+                    let window = match web_sys::window() {
+                        Some(w) => w,
+                        None => return,
+                    };
+                    let viewport_width = window.inner_width().unwrap_or(1280.into()).as_f64().unwrap_or(1280.0);
+                    let viewport_height = window.inner_height().unwrap_or(720.into()).as_f64().unwrap_or(720.0);
+
+                    // Set canvas size
+                    canvas.set_width(viewport_width as u32);
+                    canvas.set_height(viewport_height as u32);
+
+                    // Clear canvas
+                    ctx.clear_rect(0.0, 0.0, viewport_width, viewport_height);
+
+                    // Keep the before/after preview in the camera panel live
+                    if open_panel() == Some(Panel::Camera) && camera_enabled() && camera_video.ready_state() >= 2 {
+                        draw_adjust_preview(&camera_video);
+                    }
+
+                    with_compositor(viewport_width, viewport_height, &mut |compositor| {
+                        compositor.draw(&ctx, viewport_width, viewport_height);
+                    });
+                }
+            }
+        }
+    };
+
+    use_effect(move || {
+        // Only start camera if it's enabled
+        if camera_enabled() {
+            spawn(async move {
+                // Get user media
+                match media::user_media(Device::Camera, &camera_constraints()).await {
+                    Ok(media_stream) => {
+                        // Store the stream so we can stop it later
+                        camera_stream.set(Some(media_stream.clone()));
+
+                        // Set the stream to video element if we have a reference
+                        if let Some(video_elem) = camera_video_ref() {
+                            video_elem.set_src_object(Some(&media_stream));
+                            let _ = video_elem.play();
+                        }
+                    }
+                    Err(error) => {
+                        camera_enabled.set(false);
+                        report_error(error);
+                    }
+                }
+            });
+        }
+    });
+
+    // Global keyboard shortcuts
+    use_effect(move || {
+        let window = match web_sys::window() {
+            Some(w) => w,
+            None => return,
+        };
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            if is_typing_target(&event) || event.ctrl_key() || event.meta_key() || event.alt_key() {
+                return;
+            }
+
+            match event.key().as_str() {
+                key if key.eq_ignore_ascii_case(FREEZE_HOTKEY) => toggle_screen_freeze(),
+                key if key.eq_ignore_ascii_case(PRIVACY_HOTKEY) && camera_enabled() => camera_private.set(!camera_private()),
+                key if key.eq_ignore_ascii_case(MARKER_HOTKEY) && recording.peek().is_capturing() => drop_marker(),
+                key => {
+                    if let Some(next) = Layout::from_hotkey(key) {
+                        switch_layout(next);
+                    } else {
+                        let curtain_index = curtains.read().iter().position(|curtain| curtain.matches_key(key));
+                        let scene_index = scenes.read().iter().position(|scene| scene.matches_key(key));
+                        match (curtain_index, scene_index) {
+                            (Some(index), _) => toggle_curtain(index),
+                            (None, Some(index)) => recall_scene(index),
+                            (None, None) => return,
+                        }
+                    }
+                }
+            }
+            event.prevent_default();
+        }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+
+        window
+            .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
+            .ok();
+
+        // Leak closure to keep it alive
+        closure.forget();
+    });
+
+    // Start render loop with high-frequency setInterval (smoother than before)
+    use_effect(move || {
+        let window = match web_sys::window() {
+            Some(w) => w,
+            None => return,
+        };
+        let render_loop_clone = render_loop;
+
+        let closure = Closure::wrap(Box::new(move || {
+            render_loop_clone();
+        }) as Box<dyn Fn()>);
+
+        // 60fps = ~16.67ms, using 16ms for smoother rendering
+        let id = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                16,
+            )
+            .unwrap_or(-1);
+
+        animation_frame_id.set(Some(id));
+
+        // Leak closure to keep it alive
+        closure.forget();
+    });
+
+    // Function to start screen sharing
+
+    rsx! {
+        document::Style { "{RECORDER_CSS}" }
+        div {
+            class: "demrec-recorder",
+            style: "position: relative; width: 100vw; height: 100vh; overflow: hidden; background-color: #0f1116; color: white; font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif;",
+
+            // Canvas for rendering both videos
+            canvas {
+                id: "main-canvas",
+                style: format!("position: absolute; top: 0; left: 0; width: 100%; height: 100%; cursor: {};", cursor_state()),
+                onmousemove: move |event| {
+                    let mouse_x = event.client_coordinates().x;
+                    let mouse_y = event.client_coordinates().y;
+
+                    if is_pointer_mode() {
+                        // Always update pointer position when in pointer mode
+                        cursor_state.set("none"); // Hide cursor when pointer tool is active
+                        pointer_position.set((mouse_x, mouse_y));
+                    } else if is_drawing_crop() {
+                        crop_end.set((mouse_x, mouse_y));
+                    } else if is_drawing_redaction() {
+                        redact_end.set((mouse_x, mouse_y));
+                    } else if is_dragging() {
+                        cursor_state.set("grabbing");
+                        let offset = drag_offset();
+                        let new_x = mouse_x - offset.0;
+                        let new_y = mouse_y - offset.1;
+
+                        // Get viewport dimensions
+                        let window = match web_sys::window() {
+                            Some(w) => w,
+                            None => return,
+                        };
+                        let viewport_width = window.inner_width().unwrap_or(1280.into()).as_f64().unwrap_or(1280.0);
+                        let viewport_height = window.inner_height().unwrap_or(720.into()).as_f64().unwrap_or(720.0);
+
+                        let current_size = size();
+
+                        // Clamp position to keep box within viewport
+                        let clamped_x = new_x.max(0.0).min(viewport_width - current_size.0);
+                        let clamped_y = new_y.max(0.0).min(viewport_height - current_size.1);
+
+                        position.set((clamped_x, clamped_y));
+                    } else if is_resizing() {
+                        cursor_state.set("nwse-resize");
+                        let _start = resize_start();
+                        let corner = resize_corner();
+                        let pos = position();
+                        let current_size = size();
+
+                        let mut new_width = current_size.0;
+                        let mut new_height = current_size.1;
+
+                        // Calculate new dimensions based on which corner is being dragged
+                        if corner.contains("right") {
+                            new_width = (mouse_x - pos.0).clamp(100.0, 600.0);
+                        }
+                        if corner.contains("bottom") {
+                            new_height = (mouse_y - pos.1).clamp(100.0, 400.0);
+                        }
+
+                        // Allow free aspect ratio changes - no constraint
+                        size.set((new_width, new_height));
+                    } else {
+                        // Update cursor based on mouse position when not dragging/resizing
+                        update_cursor(mouse_x, mouse_y);
+                    }
+                },
+                onmousedown: move |event| {
+                    let mouse_x = event.client_coordinates().x;
+                    let mouse_y = event.client_coordinates().y;
+
+                    if is_pointer_mode() {
+                        // In pointer mode, just update pointer position
+                        pointer_position.set((mouse_x, mouse_y));
+                        event.stop_propagation();
+                    } else if is_crop_mode() {
+                        is_drawing_crop.set(true);
+                        crop_start.set((mouse_x, mouse_y));
+                        crop_end.set((mouse_x, mouse_y));
+                        event.stop_propagation();
+                    } else if is_redact_mode() {
+                        is_drawing_redaction.set(true);
+                        redact_start.set((mouse_x, mouse_y));
+                        redact_end.set((mouse_x, mouse_y));
+                        event.stop_propagation();
+                    } else if is_picking_key_color() {
+                        // Sample the key color from the raw camera frame under the cursor
+                        let (viewport_width, viewport_height) = viewport_size();
+                        let camera_rect = layout_placement(viewport_width, viewport_height).camera;
+                        let (pos, cam_size) = ((camera_rect.0, camera_rect.1), (camera_rect.2, camera_rect.3));
+                        if let (Some(camera_video), Some(work)) = (camera_video_ref(), camera_work()) {
+                            let video_width = camera_video.video_width() as f64;
+                            let video_height = camera_video.video_height() as f64;
+                            let rel_x = (mouse_x - pos.0) / cam_size.0;
+                            let rel_y = (mouse_y - pos.1) / cam_size.1;
+
+                            if video_width > 0.0 && (0.0..=1.0).contains(&rel_x) && (0.0..=1.0).contains(&rel_y) {
+                                let src = geometry::camera_source_rect(video_width, video_height, camera_zoom(), cam_size.0 / cam_size.1);
+                                // The bubble is mirrored
+                                let video_x = src.0 + (1.0 - rel_x) * src.2;
+                                let video_y = src.1 + rel_y * src.3;
+                                if let Some(color) = camera::sample_color(&camera_video, &work, video_x, video_y) {
+                                    chroma_key.write().key_color = color;
+                                    chroma::save_chroma_key(&chroma_key());
+                                }
+                            }
+                        }
+                        is_picking_key_color.set(false);
+                        event.stop_propagation();
+                    } else {
+                        let pos = position();
+                        let current_size = size();
+
+                        // Check if click is on the camera bubble
+                        if layer_at(mouse_x, mouse_y) == Some(LayerId::Camera) {
+
+                            // Check if click is in corner zones (20px from edges)
+                            let corner_size = 20.0;
+                            let rel_x = mouse_x - pos.0;
+                            let rel_y = mouse_y - pos.1;
+
+                            let near_right = rel_x > current_size.0 - corner_size;
+                            let near_bottom = rel_y > current_size.1 - corner_size;
+
+                            if near_right && near_bottom {
+                                is_resizing.set(true);
+                                resize_corner.set("bottom-right");
+                                resize_start.set((mouse_x, mouse_y));
+                            } else {
+                                is_dragging.set(true);
+                                drag_offset.set((mouse_x - pos.0, mouse_y - pos.1));
+                            }
+                            event.stop_propagation();
+                        }
+                    }
+                },
+                onmouseup: move |event| {
+                    // Turn off pointer mode on any mouseup
+                    if is_pointer_mode() {
+                        is_pointer_mode.set(false);
+                    }
+
+                    if is_drawing_crop() {
+                        is_drawing_crop.set(false);
+
+                        // Calculate crop bounds in screen video coordinates
+                        if let Some(screen_video) = screen_video_ref() {
+                            if is_screen_sharing() && screen_video.ready_state() >= 2 {
+                                let video_width = screen_video.video_width() as f64;
+                                let video_height = screen_video.video_height() as f64;
+
+                                let (viewport_width, viewport_height) = viewport_size();
+
+                                // Calculate current video position on canvas, ignoring any existing crop
+                                let area = layout_placement(viewport_width, viewport_height).screen;
+                                let full_screen = ScreenTransform::fit(
+                                    (0.0, 0.0, video_width, video_height),
+                                    frame_style().screen_area(area),
+                                );
+
+                                // Convert crop selection to video coordinates
+                                let selection = selection_rect(crop_start(), crop_end());
+                                let crop = full_screen
+                                    .and_then(|transform| transform.rect_to_video(selection, (video_width, video_height)));
+
+                                if let Some(crop) = crop.filter(|crop| crop.2 > 10.0 && crop.3 > 10.0) {
+                                    crop_bounds.set(Some(crop));
+                                    is_crop_mode.set(false);
+                                }
+                            }
+                        }
+                    }
+
+                    if is_drawing_redaction() {
+                        is_drawing_redaction.set(false);
+
+                        // Store the region in screen video coordinates so it follows crops
+                        if let (Some(screen_video), Some(transform)) = (screen_video_ref(), screen_transform()) {
+                            let video_width = screen_video.video_width() as f64;
+                            let video_height = screen_video.video_height() as f64;
+                            let selection = selection_rect(redact_start(), redact_end());
+                            let region = transform.rect_to_video(selection, (video_width, video_height));
+
+                            if let Some((x, y, width, height)) = region.filter(|region| region.2 > 4.0 && region.3 > 4.0) {
+                                redactions.write().push(RedactionRegion {
+                                    x,
+                                    y,
+                                    width,
+                                    height,
+                                    style: redact_style(),
+                                });
+                            }
+                        }
+                    }
+
+                    is_dragging.set(false);
+                    is_resizing.set(false);
+                    // Update cursor based on final mouse position
+                    let mouse_x = event.client_coordinates().x;
+                    let mouse_y = event.client_coordinates().y;
+                    update_cursor(mouse_x, mouse_y);
+                },
+                onmouseleave: move |_| {
+                    is_dragging.set(false);
+                    is_resizing.set(false);
+                    cursor_state.set("default");
+                },
+                onmounted: move |_| {
+                    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                        if let Some(element) = document.get_element_by_id("main-canvas") {
+                            if let Ok(canvas_elem) = element.dyn_into::<web_sys::HtmlCanvasElement>() {
+                                canvas_ref.set(Some(canvas_elem));
+                            }
+                        }
+                    }
+                },
+            }
+
+            // Hidden video elements
+            video {
+                id: "camera-video",
+                style: "display: none;",
+                autoplay: "true",
+                playsinline: "true",
+                muted: "true",
+                onmounted: move |_| {
+                    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                        if let Some(element) = document.get_element_by_id("camera-video") {
+                            if let Ok(video_elem) = element.dyn_into::<web_sys::HtmlVideoElement>() {
+                                camera_video_ref.set(Some(video_elem));
+                            }
+                        }
+                    }
+                },
+            }
+
+            video {
+                id: "screen-video",
+                style: "display: none;",
+                autoplay: "true",
+                playsinline: "true",
+                muted: "true",
+                onmounted: move |_| {
+                    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                        if let Some(element) = document.get_element_by_id("screen-video") {
+                            if let Ok(video_elem) = element.dyn_into::<web_sys::HtmlVideoElement>() {
+                                screen_video_ref.set(Some(video_elem));
+                            }
+                        }
+                    }
+                },
+            }
+
+
+            // Recordings library button (above the scenes)
+            button {
+                style: format!("position: absolute; bottom: 716px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if show_library() { "#8b5cf6" } else { "#6366f1" }
+                ),
+                title: "Recordings",
+                disabled: !capabilities().opfs,
+                onclick: move |_| show_library.set(!show_library()),
+                // Library icon
+                "📼"
+            },
+
+            // Scenes button (top)
+            button {
+                style: format!("position: absolute; bottom: 658px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if open_panel() == Some(Panel::Scenes) { "#8b5cf6" } else { "#6366f1" }
+                ),
+                title: "Scenes",
+                onclick: move |_| {
+                    open_panel.set(if open_panel() == Some(Panel::Scenes) { None } else { Some(Panel::Scenes) });
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+                },
+                // Scenes icon
+                "🎞"
+            },
+
+            // Curtain button (2nd from top)
+            button {
+                style: format!("position: absolute; bottom: 600px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if active_curtain().is_some() { "#f59e0b" } else if open_panel() == Some(Panel::Curtains) { "#8b5cf6" } else { "#6366f1" }
+                ),
+                title: "Curtains",
+                onclick: move |_| {
+                    open_panel.set(if open_panel() == Some(Panel::Curtains) { None } else { Some(Panel::Curtains) });
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+                },
+                // Curtain icon
+                "🎬"
+            },
+
+            // Freeze screen button (3rd from top)
+            button {
+                style: format!("position: absolute; bottom: 542px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_screen_frozen() { "#0ea5e9" } else { "#6366f1" }
+                ),
+                title: "Freeze screen (F)",
+                onclick: move |_| toggle_screen_freeze(),
+                disabled: !is_screen_sharing(),
+                // Freeze icon
+                "❄"
+            },
+
+            // Redaction tool button (4th from top)
+            button {
+                style: format!("position: absolute; bottom: 484px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_redact_mode() { "#8b5cf6" } else if !redactions.read().is_empty() { "#a78bfa" } else { "#6366f1" }
+                ),
+                onclick: move |_| {
+                    is_redact_mode.set(!is_redact_mode());
+                    is_drawing_redaction.set(false);
+                    open_panel.set(None);
+                    is_crop_mode.set(false);
+                    is_drawing_crop.set(false);
+                    is_pointer_mode.set(false);
+                },
+                // Redaction icon
+                "▦"
+            },
+
+            // Pointer tool button (5th from top)
+            button {
+                style: format!("position: absolute; bottom: 426px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_pointer_mode() { "#8b5cf6" } else { "#6366f1" }
+                ),
+                onmousedown: move |event| {
+                    // Enable pointer mode and disable crop/redact modes
+                    is_pointer_mode.set(true);
+                    is_crop_mode.set(false);
+                    is_drawing_crop.set(false);
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+
+                    // Set initial pointer position to button center
+                    let button_x = 44.0; // 20px left + 24px center of 48px button
+                    let button_y = event.client_coordinates().y; // Use actual button y position
+                    pointer_position.set((button_x, button_y));
+
+                    event.stop_propagation();
+                },
+                // Pointer icon
+                "👆"
+            },
+
+            // Marker flash over the preview; drawn on the page, not the canvas, so it isn't recorded
+            if is_marker_flashing() {
+                div {
+                    style: "position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: 5; box-sizing: border-box; border: 6px solid #fbbf24; background-color: rgba(251, 191, 36, 0.12); pointer-events: none;",
+                }
+            }
+
+            // Record button (6th from top)
+            button {
+                style: format!("position: absolute; bottom: 368px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if recording().is_capturing() { "#ef4444" } else { "#dc2626" }
+                ),
+                onclick: move |_| {
+                    if recording().is_busy() {
+                        // Stop recording, or cancel the countdown
+                        stop_recording();
+                    } else if sources_ready() {
+                        start_recording();
+                    } else {
+                        // Check permissions and sources before the countdown
+                        show_readiness.set(true);
+                    }
+                },
+                // Record icon
+                {if recording().is_busy() { "⏹" } else { "⏺" }}
+            },
+
+            // Pause button, next to the record button while recording
+            if recording().is_capturing() {
+                button {
+                    style: format!("position: absolute; bottom: 368px; left: 78px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                        if recording() == RecordingState::Paused { "#f59e0b" } else { "#374151" }
+                    ),
+                    title: if recording() == RecordingState::Paused { "Resume recording" } else { "Pause recording" },
+                    onclick: move |_| {
+                        if let Some(recorder) = media_recorder() {
+                            if send_recording(RecordingEvent::Pause) {
+                                let _ = recorder.pause();
+                            } else if send_recording(RecordingEvent::Resume) {
+                                let _ = recorder.resume();
+                            }
+                        }
+                    },
+                    {if recording() == RecordingState::Paused { "▶" } else { "⏸" }}
+                }
+                button {
+                    style: "position: absolute; bottom: 368px; left: 136px; z-index: 10; width: 48px; height: 48px; background-color: #374151; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    title: format!("Drop a marker ({})", MARKER_HOTKEY.to_uppercase()),
+                    onclick: move |_| drop_marker(),
+                    "🔖"
+                }
+            }
+
+            // Label for the marker just dropped, without taking the keyboard from the
+            // hotkeys until it is clicked; recording carries on while typing
+            if let Some(index) = editing_marker().filter(|_| recording().is_capturing()) {
+                div {
+                    style: "position: absolute; bottom: 368px; left: 194px; z-index: 10; height: 48px; box-sizing: border-box; display: flex; align-items: center; gap: 8px; padding: 0 12px; background-color: #1f2937; color: white; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: system-ui, sans-serif; font-size: 13px;",
+                    span {
+                        style: "color: #fbbf24; font-variant-numeric: tabular-nums;",
+                        {recording_markers.read().get(index).map(|marker| library::format_duration(marker.time_ms)).unwrap_or_default()}
+                    }
+                    input {
+                        style: "width: 180px; background-color: #111827; color: white; border: 1px solid #374151; border-radius: 6px; padding: 6px 8px; font-size: 13px;",
+                        placeholder: format!("Marker {} label (optional)", index + 1),
+                        value: recording_markers.read().get(index).map(|marker| marker.label.clone()).unwrap_or_default(),
+                        oninput: move |event: FormEvent| {
+                            if let Some(marker) = recording_markers.write().get_mut(index) {
+                                marker.label = event.value();
+                            }
+                        },
+                        onchange: move |_| save_session_markers(),
+                        onkeydown: move |event: KeyboardEvent| {
+                            if matches!(event.key(), Key::Enter | Key::Escape) {
+                                save_session_markers();
+                                editing_marker.set(None);
+                            }
+                        },
+                    }
+                    label {
+                        style: "display: flex; align-items: center; gap: 4px; color: #9ca3af; font-size: 11px;",
+                        title: "Flash the preview when a marker is dropped; the recording isn't affected",
+                        input {
+                            r#type: "checkbox",
+                            checked: marker_flash(),
+                            onchange: move |event: FormEvent| {
+                                marker_flash.set(event.checked());
+                                markers::save_marker_flash(event.checked());
+                            },
+                        }
+                        "Flash"
+                    }
+                    button {
+                        style: "background: none; border: none; color: #9ca3af; cursor: pointer; font-size: 16px;",
+                        title: "Done",
+                        onclick: move |_| {
+                            save_session_markers();
+                            editing_marker.set(None);
+                        },
+                        "×"
+                    }
+                }
+            }
+
+            // Picture-in-Picture button (7th from top)
+            button {
+                style: format!("position: absolute; bottom: 310px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_pip_active() { "#10b981" } else { "#6366f1" }
+                ),
+                onclick: move |_| {
+                    web_sys::console::log_1(&"PiP button clicked".into());
+                    if is_pip_active() {
+                        web_sys::console::log_1(&"Exiting PiP".into());
+                        // Exit PiP
+                        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                            if let Ok(promise) = js_sys::Reflect::get(&document, &"exitPictureInPicture".into()) {
+                                if let Ok(func) = promise.dyn_into::<js_sys::Function>() {
+                                    let mut is_pip_clone = is_pip_active;
+                                    spawn(async move {
+                                        if let Ok(promise) = func.call0(&document) {
+                                            if let Ok(promise) = promise.dyn_into::<js_sys::Promise>() {
+                                                let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+                                            }
+                                        }
+                                        is_pip_clone.set(false);
+                                    });
+                                }
+                            }
+                        }
+                    } else {
+                        web_sys::console::log_1(&"Entering PiP".into());
+                        // Enter PiP
+                        if let Some(canvas) = canvas_ref() {
+                            web_sys::console::log_1(&"Got canvas".into());
+                            let mut pip_video_clone = pip_video_element;
+                            let is_pip_clone = is_pip_active;
+                            spawn(async move {
+                                web_sys::console::log_1(&"In spawn".into());
+                                if let Ok(stream) = canvas.capture_stream() {
+                                    web_sys::console::log_1(&"Got stream".into());
+                                    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                                        web_sys::console::log_1(&"Got document".into());
+                                        // Create or reuse video element
+                                        let video = if let Some(existing_video) = pip_video_clone() {
+                                            web_sys::console::log_1(&"Using existing video".into());
+                                            existing_video
+                                        } else {
+                                            web_sys::console::log_1(&"Creating new video".into());
+                                            if let Ok(video_elem) = document.create_element("video") {
+                                                if let Ok(video) = video_elem.dyn_into::<web_sys::HtmlVideoElement>() {
+                                                    // Hide the video element
+                                                    if let Some(html_elem) = video.dyn_ref::<web_sys::HtmlElement>() {
+                                                        html_elem.style().set_property("display", "none").ok();
+                                                    }
+                                                    if let Some(body) = document.body() {
+                                                        body.append_child(&video).ok();
+                                                    }
+                                                    pip_video_clone.set(Some(video.clone()));
+                                                    video
+                                                } else {
+                                                    web_sys::console::log_1(&"Failed to cast to video".into());
+                                                    return;
+                                                }
+                                            } else {
+                                                web_sys::console::log_1(&"Failed to create video element".into());
+                                                return;
+                                            }
+                                        };
+                                        
+                                        web_sys::console::log_1(&"Setting up video".into());
+                                        video.set_src_object(Some(&stream));
+                                        video.set_muted(true);
+                                        let _ = video.play();
+                                        
+                                        // Wait for video metadata to load before requesting PiP
+                                        let video_clone = video.clone();
+                                        let mut is_pip_clone2 = is_pip_clone;
+                                        let callback = wasm_bindgen::closure::Closure::wrap(Box::new(move || {
+                                            web_sys::console::log_1(&"Video metadata loaded, requesting PiP".into());
+                                            if let Ok(promise) = js_sys::Reflect::get(&video_clone, &"requestPictureInPicture".into()) {
+                                                web_sys::console::log_1(&"Got PiP method".into());
+                                                if let Ok(func) = promise.dyn_into::<js_sys::Function>() {
+                                                    web_sys::console::log_1(&"Calling PiP".into());
+                                                    if let Ok(_promise) = func.call0(&video_clone) {
+                                                        web_sys::console::log_1(&"PiP call succeeded".into());
+                                                        is_pip_clone2.set(true);
+                                                        
+                                                        // Add event listener for when PiP window is closed
+                                                        let video_clone2 = video_clone.clone();
+                                                        let mut is_pip_clone3 = is_pip_clone2;
+                                                        let mut stop_recording_clone = stop_recording;
+                                                        
+                                                        let leave_pip_callback = wasm_bindgen::closure::Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                                                            web_sys::console::log_1(&"PiP window closed, stopping recording".into());
+                                                            is_pip_clone3.set(false);
+                                                            
+                                                            // Stop recording if it's active
+                                                            if recording.peek().is_capturing() {
+                                                                stop_recording_clone();
+                                                            }
+                                                        }) as Box<dyn FnMut(web_sys::Event)>);
+                                                        
+                                                        video_clone2.add_event_listener_with_callback("leavepictureinpicture", leave_pip_callback.as_ref().unchecked_ref()).ok();
+                                                        leave_pip_callback.forget();
+                                                        
+                                                    } else {
+                                                        web_sys::console::log_1(&"Failed to call PiP".into());
+                                                    }
+                                                } else {
+                                                    web_sys::console::log_1(&"Failed to cast to function".into());
+                                                }
+                                            } else {
+                                                web_sys::console::log_1(&"No PiP method found".into());
+                                            }
+                                        }) as Box<dyn FnMut()>);
+                                        
+                                        video.add_event_listener_with_callback("loadedmetadata", callback.as_ref().unchecked_ref()).ok();
+                                        callback.forget(); // Keep callback alive
+                                    }
+                                } else {
+                                    web_sys::console::log_1(&"Failed to get stream".into());
+                                }
+                            });
+                        } else {
+                            web_sys::console::log_1(&"No canvas found".into());
+                        }
+                    }
+                },
+                title: if capabilities().video_pip { "Picture-in-Picture preview" } else { "Picture-in-Picture isn't supported by this browser" },
+                disabled: !capabilities().video_pip,
+                // PiP icon
+                "🖼"
+            },
+
+            // Reset zoom button (8th from top)
+            button {
+                style: format!("position: absolute; bottom: 252px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if crop_bounds().is_some() { "#f59e0b" } else { "#6b7280" }
+                ),
+                onclick: move |_| {
+                    crop_bounds.set(None);
+                    is_crop_mode.set(false);
+                },
+                disabled: crop_bounds().is_none(),
+                // Reset/full screen icon
+                "⤢"
+            },
+
+            // Crop/zoom button (9th from top)
+            button {
+                style: format!("position: absolute; bottom: 194px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_crop_mode() { "#8b5cf6" } else if crop_bounds().is_some() { "#a78bfa" } else { "#6366f1" }
+                ),
+                onclick: move |_| {
+                    is_crop_mode.set(!is_crop_mode());
+                    is_drawing_crop.set(false);
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+                },
+                disabled: !is_screen_sharing(),
+                // Crop icon
+                "⬚"
+            },
+
+            // Screen share button (10th from top)
+            button {
+                style: format!("position: absolute; bottom: 136px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if is_screen_sharing() { "#ef4444" } else { "#3b82f6" }
+                ),
+                onclick: move |_| {
+                    if is_screen_sharing() {
+                        // Stop screen share
+                        if let Some(stream) = screen_stream() {
+                            let tracks = stream.get_tracks();
+                            for i in 0..tracks.length() {
+                                let track = tracks.get(i);
+                                if let Ok(media_track) = track.dyn_into::<web_sys::MediaStreamTrack>() {
+                                    media_track.stop();
+                                }
+                            }
+                        }
+                        screen_stream.set(None);
+                        is_screen_sharing.set(false);
+                        is_screen_frozen.set(false);
+                    } else {
+                        start_screen_share();
+                    }
+                },
+                title: if capabilities().display_media { "Share screen" } else { "Screen sharing isn't supported by this browser" },
+                disabled: !is_screen_sharing() && !capabilities().display_media,
+                // Screen share specific icons  
+                {if is_screen_sharing() { "📴" } else { "🖥" }}
+            },
+
+            // Screen settings button (right of screen share)
+            button {
+                style: format!("position: absolute; bottom: 136px; left: 78px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if open_panel() == Some(Panel::Screen) { "#8b5cf6" } else { "#6b7280" }
+                ),
+                title: "Screen settings",
+                onclick: move |_| {
+                    open_panel.set(if open_panel() == Some(Panel::Screen) { None } else { Some(Panel::Screen) });
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+                },
+                // Settings icon
+                "⚙"
+            },
+
+            // Camera toggle button (above camera zoom)
+            button {
+                style: format!("position: absolute; bottom: 78px; left: 20px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;", 
+                    if camera_enabled() { "#10b981" } else { "#6b7280" }
+                ),
+                onclick: move |_| {
+                    if camera_enabled() {
+                        stop_camera();
+                    } else {
+                        start_camera();
+                    }
+                },
+                disabled: !camera_enabled() && !capabilities().user_media,
+                // Camera icon
+                {if camera_enabled() { "📹" } else { "📴" }}
+            },
+
+            // Camera privacy toggle (right of camera toggle)
+            button {
+                style: format!("position: absolute; bottom: 78px; left: 78px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if camera_private() { "#f59e0b" } else { "#6b7280" }
+                ),
+                title: "Camera privacy (C)",
+                onclick: move |_| camera_private.set(!camera_private()),
+                disabled: !camera_enabled(),
+                // Privacy icon
+                "🙈"
+            },
+
+            // Camera settings button (right of privacy toggle)
+            button {
+                style: format!("position: absolute; bottom: 78px; left: 136px; z-index: 10; width: 48px; height: 48px; background-color: {}; color: white; border: none; border-radius: 12px; cursor: pointer; display: flex; align-items: center; justify-content: center; font-size: 18px; transition: all 0.2s; box-shadow: 0 2px 8px rgba(0,0,0,0.3); font-family: monospace;",
+                    if open_panel() == Some(Panel::Camera) { "#8b5cf6" } else { "#6b7280" }
+                ),
+                title: "Camera settings",
+                onclick: move |_| {
+                    open_panel.set(if open_panel() == Some(Panel::Camera) { None } else { Some(Panel::Camera) });
+                    is_redact_mode.set(false);
+                    is_drawing_redaction.set(false);
+                },
+                // Settings icon
+                "⚙"
+            },
+
+            // Camera zoom slider (bottom)
+            div {
+                style: "position: absolute; bottom: 20px; left: 20px; z-index: 10; width: 160px; height: 48px; background-color: rgba(31, 41, 55, 0.9); padding: 6px 8px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); display: flex; flex-direction: column; justify-content: center;",
+                div {
+                    style: "color: white; font-size: 10px; margin-bottom: 2px; font-family: monospace; text-align: center;",
+                    {format!("Zoom: {:.1}x", camera_zoom())}
+                }
+                input {
+                    r#type: "range",
+                    min: "1.0",
+                    max: "3.0",
+                    step: "0.1",
+                    value: format!("{}", camera_zoom()),
+                    style: "width: 100%; accent-color: #6366f1; height: 16px;",
+                    oninput: move |event| {
+                        if let Ok(value) = event.value().parse::<f64>() {
+                            camera_zoom.set(value);
+                        }
+                    }
+                }
+            }
+
+            // Frozen screen border (page UI only, not part of the recorded canvas)
+            if is_screen_frozen() {
+                div {
+                    style: "position: absolute; top: 0; left: 0; width: 100vw; height: 100vh; z-index: 5; pointer-events: none; box-sizing: border-box; border: 4px solid #0ea5e9;",
+                }
+            }
+
+            // Status badges (page UI only, not part of the recorded canvas)
+            div {
+                style: "position: absolute; top: 20px; left: 50%; transform: translateX(-50%); z-index: 10; display: flex; flex-direction: column; align-items: center; gap: 8px; pointer-events: none; font-family: monospace; font-size: 14px; color: white;",
+                if is_screen_frozen() {
+                    div {
+                        style: "padding: 8px 16px; background-color: #0ea5e9; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3);",
+                        "❄ Screen frozen: press F to resume"
+                    }
+                }
+                if let Some(curtain) = active_curtain().and_then(|index| curtains.read().get(index).cloned()) {
+                    div {
+                        style: "padding: 8px 16px; background-color: #f59e0b; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3);",
+                        {format!(
+                            "🎬 Curtain up: {}{} (press {} to return)",
+                            curtain.name,
+                            if curtain.mute_mic { ", mic muted" } else { "" },
+                            curtain.key.to_uppercase(),
+                        )}
+                    }
+                }
+                if camera_enabled() && camera_private() {
+                    div {
+                        style: "padding: 8px 16px; background-color: #f59e0b; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3);",
+                        {format!("🙈 Camera private ({}): press C to show", camera_privacy().label())}
+                    }
+                }
+            }
+
+            // Camera settings panel
+            if open_panel() == Some(Panel::Camera) {
+                div {
+                    style: "position: absolute; top: 20px; right: 20px; z-index: 10; width: 280px; max-height: calc(100vh - 40px); overflow-y: auto; background-color: rgba(31, 41, 55, 0.9); padding: 12px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); color: white; font-family: monospace; font-size: 12px; display: flex; flex-direction: column; gap: 8px;",
+                    div {
+                        style: "font-size: 14px; font-weight: bold;",
+                        "Camera"
+                    }
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; gap: 8px;",
+                        "Bubble shape:"
+                        select {
+                            value: camera_shape().label(),
+                            onchange: move |event| {
+                                if let Some(shape) = CameraShape::from_label(&event.value()) {
+                                    camera_shape.set(shape);
+                                }
+                            },
+                            for shape in CameraShape::ALL {
+                                option { value: shape.label(), {shape.label()} }
+                            }
+                        }
+                    }
+
+                    // Privacy
+                    div {
+                        style: "font-weight: bold; margin-top: 4px;",
+                        "Privacy"
+                    }
+                    label {
+                        style: "display: flex; align-items: center; gap: 4px;",
+                        input {
+                            r#type: "checkbox",
+                            checked: camera_private(),
+                            onchange: move |event| camera_private.set(event.checked()),
+                        }
+                        "Hide camera (C)"
+                    }
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; gap: 8px;",
+                        "Style:"
+                        select {
+                            value: camera_privacy().label(),
+                            onchange: move |event| {
+                                if let Some(privacy) = CameraPrivacy::from_label(&event.value()) {
+                                    camera_privacy.set(privacy);
+                                }
+                            },
+                            for privacy in CameraPrivacy::ALL {
+                                option { value: privacy.label(), {privacy.label()} }
+                            }
+                        }
+                    }
+                    if camera_privacy() == CameraPrivacy::Avatar {
+                        div {
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            label {
+                                style: "flex: 1; cursor: pointer; color: #93c5fd;",
+                                {if camera_avatar.read().is_some() { "Change avatar" } else { "Choose avatar image" }}
+                                input {
+                                    r#type: "file",
+                                    accept: "image/*",
+                                    style: "display: none;",
+                                    onchange: move |event| {
+                                        spawn(async move {
+                                            if let Some(url) = image::read_data_url(&event).await {
+                                                report_result(privacy::save_avatar(Some(&url)));
+                                                camera_avatar.set(image::load_image(&url).map(|avatar| (url, avatar)));
+                                            }
+                                        });
+                                    },
+                                }
+                            }
+                            if camera_avatar.read().is_some() {
+                                button {
+                                    onclick: move |_| {
+                                        report_result(privacy::save_avatar(None));
+                                        camera_avatar.set(None);
+                                    },
+                                    "Remove"
+                                }
+                            }
+                        }
+                    }
+
+                    // Green screen
+                    div {
+                        style: "font-weight: bold; margin-top: 8px;",
+                        "Green screen"
+                    }
+                    label {
+                        style: "display: flex; align-items: center; gap: 4px;",
+                        input {
+                            r#type: "checkbox",
+                            checked: chroma_key().enabled,
+                            onchange: move |event| {
+                                chroma_key.write().enabled = event.checked();
+                                chroma::save_chroma_key(&chroma_key());
+                            },
+                        }
+                        "Key out background"
+                    }
+                    div {
+                        style: "display: flex; align-items: center; gap: 6px;",
+                        "Key color:"
+                        input {
+                            r#type: "color",
+                            value: chroma::to_hex(chroma_key().key_color),
+                            oninput: move |event| {
+                                if let Some(color) = chroma::from_hex(&event.value()) {
+                                    chroma_key.write().key_color = color;
+                                    chroma::save_chroma_key(&chroma_key());
+                                }
+                            },
+                        }
+                        button {
+                            style: format!("background-color: {}; color: white; border: none; padding: 2px 8px; border-radius: 6px; cursor: pointer;",
+                                if is_picking_key_color() { "#8b5cf6" } else { "#4b5563" }
+                            ),
+                            disabled: !camera_enabled(),
+                            onclick: move |_| is_picking_key_color.set(!is_picking_key_color()),
+                            {if is_picking_key_color() { "Click the camera…" } else { "Pick" }}
+                        }
+                    }
+                    for (label, value, setter) in [
+                        ("Tolerance", chroma_key().tolerance, (|key: &mut ChromaKey, value: f64| key.tolerance = value) as fn(&mut ChromaKey, f64)),
+                        ("Softness", chroma_key().softness, |key: &mut ChromaKey, value: f64| key.softness = value),
+                        ("Spill", chroma_key().spill, |key: &mut ChromaKey, value: f64| key.spill = value),
+                    ] {
+                        div {
+                            key: "{label}",
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            span {
+                                style: "width: 72px;",
+                                {format!("{}: {:.2}", label, value)}
+                            }
+                            input {
+                                r#type: "range",
+                                min: "0",
+                                max: if label == "Spill" { "1" } else { "0.5" },
+                                step: "0.01",
+                                value: format!("{}", value),
+                                style: "flex: 1; accent-color: #6366f1;",
+                                oninput: move |event| {
+                                    if let Ok(value) = event.value().parse::<f64>() {
+                                        setter(&mut chroma_key.write(), value);
+                                        chroma::save_chroma_key(&chroma_key());
+                                    }
+                                },
+                            }
+                        }
+                    }
+
+                    // Color
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; font-weight: bold; margin-top: 8px;",
+                        "Color"
+                        button {
+                            disabled: camera_adjust().is_neutral(),
+                            onclick: move |_| {
+                                camera_adjust.set(CameraAdjustments::default());
+                                camera_lut.set(None);
+                                save_camera_adjust();
+                            },
+                            "Reset"
+                        }
+                    }
+                    canvas {
+                        id: "adjust-preview",
+                        width: "256",
+                        height: "144",
+                        style: "width: 100%; border-radius: 6px; background-color: #111827;",
+                        onmounted: move |_| {
+                            if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                                if let Some(element) = document.get_element_by_id("adjust-preview") {
+                                    if let Ok(canvas_elem) = element.dyn_into::<web_sys::HtmlCanvasElement>() {
+                                        adjust_preview_ref.set(Some(canvas_elem));
+                                    }
+                                }
+                            }
+                        },
+                    }
+                    for (label, min, value, setter) in [
+                        ("Brightness", -1.0, camera_adjust().brightness, (|adjust: &mut CameraAdjustments, value: f64| adjust.brightness = value) as fn(&mut CameraAdjustments, f64)),
+                        ("Contrast", -1.0, camera_adjust().contrast, |adjust: &mut CameraAdjustments, value: f64| adjust.contrast = value),
+                        ("Saturation", -1.0, camera_adjust().saturation, |adjust: &mut CameraAdjustments, value: f64| adjust.saturation = value),
+                        ("Warmth", -1.0, camera_adjust().temperature, |adjust: &mut CameraAdjustments, value: f64| adjust.temperature = value),
+                        ("Vignette", 0.0, camera_adjust().vignette, |adjust: &mut CameraAdjustments, value: f64| adjust.vignette = value),
+                    ] {
+                        div {
+                            key: "{label}",
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            span {
+                                style: "width: 104px;",
+                                {format!("{}: {:+.2}", label, value)}
+                            }
+                            input {
+                                r#type: "range",
+                                min: format!("{}", min),
+                                max: "1",
+                                step: "0.01",
+                                value: format!("{}", value),
+                                style: "flex: 1; accent-color: #6366f1;",
+                                ondoubleclick: move |_| {
+                                    setter(&mut camera_adjust.write(), 0.0);
+                                    save_camera_adjust();
+                                },
+                                oninput: move |event| {
+                                    if let Ok(value) = event.value().parse::<f64>() {
+                                        setter(&mut camera_adjust.write(), value);
+                                        save_camera_adjust();
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    div {
+                        style: "display: flex; align-items: center; gap: 6px;",
+                        label {
+                            style: "flex: 1; cursor: pointer; color: #93c5fd; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                            {match camera_adjust().lut_name {
+                                Some(name) => format!("LUT: {}", name),
+                                None => "Load 3D LUT (.cube)".to_string(),
+                            }}
+                            input {
+                                r#type: "file",
+                                accept: ".cube",
+                                style: "display: none;",
+                                onchange: move |event| {
+                                    spawn(async move {
+                                        let Some(files) = event.files() else {
+                                            return;
+                                        };
+                                        let Some(name) = files.files().into_iter().next() else {
+                                            return;
+                                        };
+                                        let Some(text) = files.read_file_to_string(&name).await else {
+                                            return;
+                                        };
+
+                                        match adjust::Lut::parse(&text) {
+                                            Ok(lut) => {
+                                                report_result(adjust::save_lut(&name, &text));
+                                                camera_lut.set(Some(lut));
+                                                camera_adjust.write().lut_name = Some(name);
+                                                lut_error.set(None);
+                                                save_camera_adjust();
+                                            }
+                                            Err(error) => lut_error.set(Some(format!("{}: {}", name, error))),
+                                        }
+                                    });
+                                },
+                            }
+                        }
+                        if camera_adjust().lut_name.is_some() {
+                            button {
+                                onclick: move |_| {
+                                    camera_lut.set(None);
+                                    camera_adjust.write().lut_name = None;
+                                    save_camera_adjust();
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                    if let Some(error) = lut_error() {
+                        div {
+                            style: "color: #f87171;",
+                            {error}
+                        }
+                    }
+
+                    // Layout
+                    div {
+                        style: "font-weight: bold; margin-top: 8px;",
+                        "Layout"
+                    }
+                    for choice in Layout::ALL {
+                        button {
+                            key: "{choice.hotkey()}",
+                            style: format!("display: flex; justify-content: space-between; background-color: {}; color: white; border: none; padding: 4px 8px; border-radius: 6px; cursor: pointer; font-family: monospace;",
+                                if layout() == choice { "#8b5cf6" } else { "#4b5563" }
+                            ),
+                            onclick: move |_| switch_layout(choice),
+                            span { {choice.label()} }
+                            span { {choice.hotkey()} }
+                        }
+                    }
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; gap: 8px;",
+                        "Behind camera:"
+                        select {
+                            value: match virtual_background() {
+                                VirtualBackground::BlurredScreen => "screen",
+                                VirtualBackground::Color(_) => "color",
+                                VirtualBackground::Image(_) => "image",
+                            },
+                            onchange: move |event| {
+                                let background = match event.value().as_str() {
+                                    "screen" => VirtualBackground::BlurredScreen,
+                                    "image" => VirtualBackground::Image(0),
+                                    _ => VirtualBackground::default(),
+                                };
+                                background::save_virtual_background(&background);
+                                virtual_background.set(background);
+                            },
+                            option { value: "color", "Solid color" }
+                            option { value: "screen", "Blurred screen" }
+                            option { value: "image", "Image" }
+                        }
+                    }
+                    if let VirtualBackground::Color(color) = virtual_background() {
+                        input {
+                            r#type: "color",
+                            value: color,
+                            oninput: move |event| {
+                                let background = VirtualBackground::Color(event.value());
+                                background::save_virtual_background(&background);
+                                virtual_background.set(background);
+                            },
+                        }
+                    }
+                    if let VirtualBackground::Image(selected) = virtual_background() {
+                        div {
+                            style: "display: grid; grid-template-columns: repeat(3, 1fr); gap: 6px;",
+                            for (index, background) in background_images().into_iter().enumerate() {
+                                div {
+                                    key: "{index}",
+                                    style: format!("position: relative; aspect-ratio: 16 / 9; border-radius: 6px; cursor: pointer; background-image: url({}); background-size: cover; background-position: center; border: 2px solid {};",
+                                        background.url,
+                                        if index == selected { "#8b5cf6" } else { "transparent" }
+                                    ),
+                                    onclick: move |_| {
+                                        let background = VirtualBackground::Image(index);
+                                        background::save_virtual_background(&background);
+                                        virtual_background.set(background);
+                                    },
+                                    button {
+                                        style: "position: absolute; top: 0; right: 0; background: rgba(0,0,0,0.6); color: #f87171; border: none; border-radius: 4px; cursor: pointer; font-size: 10px;",
+                                        onclick: move |event| {
+                                            event.stop_propagation();
+                                            if index < background_images.read().len() {
+                                                background_images.write().remove(index);
+                                            }
+                                            report_result(background::save_background_images(&background_images.read()));
+
+                                            // Keep the selections on the same image
+                                            if let LetterboxFill::Image(selected) = letterbox_fill() {
+                                                let fill = LetterboxFill::Image(if selected > index { selected - 1 } else { selected });
+                                                background::save_letterbox_fill(&fill);
+                                                letterbox_fill.set(fill);
+                                            }
+                                            if let VirtualBackground::Image(selected) = virtual_background() {
+                                                let background = if selected == index {
+                                                    VirtualBackground::Image(0)
+                                                } else if selected > index {
+                                                    VirtualBackground::Image(selected - 1)
+                                                } else {
+                                                    VirtualBackground::Image(selected)
+                                                };
+                                                background::save_virtual_background(&background);
+                                                virtual_background.set(background);
+                                            }
+                                        },
+                                        "✕"
+                                    }
+                                }
+                            }
+                        }
+                        label {
+                            style: "cursor: pointer; color: #93c5fd;",
+                            "Add background image"
+                            input {
+                                r#type: "file",
+                                accept: "image/*",
+                                style: "display: none;",
+                                onchange: move |event| {
+                                    spawn(async move {
+                                        let Some(url) = image::read_data_url(&event).await else {
+                                            return;
+                                        };
+                                        // Shrink large photos so they fit in browser storage
+                                        let url = image::shrink_data_url(&url, 1920.0).await.unwrap_or(url);
+                                        if let Some(loaded) = image::load_image(&url) {
+                                            background_images.write().push(BackgroundImage { url, image: loaded });
+                                            report_result(background::save_background_images(&background_images.read()));
+
+                                            let background = VirtualBackground::Image(background_images.read().len() - 1);
+                                            background::save_virtual_background(&background);
+                                            virtual_background.set(background);
+                                        }
+                                    });
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Screen settings panel
+            if open_panel() == Some(Panel::Screen) {
+                div {
+                    style: "position: absolute; top: 20px; right: 20px; z-index: 10; width: 280px; max-height: calc(100vh - 40px); overflow-y: auto; background-color: rgba(31, 41, 55, 0.9); padding: 12px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); color: white; font-family: monospace; font-size: 12px; display: flex; flex-direction: column; gap: 8px;",
+                    div {
+                        style: "font-size: 14px; font-weight: bold;",
+                        "Screen"
+                    }
+
+                    // Letterbox
+                    div {
+                        style: "font-weight: bold; margin-top: 4px;",
+                        "Letterbox"
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        "Shown around the screen share when its shape doesn't match the recording."
+                    }
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; gap: 8px;",
+                        "Fill:"
+                        select {
+                            value: match letterbox_fill() {
+                                LetterboxFill::Color(_) => "color",
+                                LetterboxFill::Gradient(_, _) => "gradient",
+                                LetterboxFill::Image(_) => "image",
+                                LetterboxFill::BlurredScreen => "screen",
+                            },
+                            onchange: move |event| {
+                                let fill = match event.value().as_str() {
+                                    "gradient" => LetterboxFill::Gradient("#1e1b4b".to_string(), "#0f172a".to_string()),
+                                    "image" => LetterboxFill::Image(0),
+                                    "screen" => LetterboxFill::BlurredScreen,
+                                    _ => LetterboxFill::default(),
+                                };
+                                background::save_letterbox_fill(&fill);
+                                letterbox_fill.set(fill);
+                            },
+                            option { value: "color", "Solid color" }
+                            option { value: "gradient", "Gradient" }
+                            option { value: "image", "Image" }
+                            option { value: "screen", "Blurred screen" }
+                        }
+                    }
+                    if let LetterboxFill::Color(color) = letterbox_fill() {
+                        input {
+                            r#type: "color",
+                            value: color,
+                            oninput: move |event| {
+                                let fill = LetterboxFill::Color(event.value());
+                                background::save_letterbox_fill(&fill);
+                                letterbox_fill.set(fill);
+                            },
+                        }
+                    }
+                    if let LetterboxFill::Gradient(top, bottom) = letterbox_fill() {
+                        div {
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            "Top:"
+                            input {
+                                r#type: "color",
+                                value: top,
+                                oninput: move |event| {
+                                    if let LetterboxFill::Gradient(_, bottom) = letterbox_fill() {
+                                        let fill = LetterboxFill::Gradient(event.value(), bottom);
+                                        background::save_letterbox_fill(&fill);
+                                        letterbox_fill.set(fill);
+                                    }
+                                },
+                            }
+                            "Bottom:"
+                            input {
+                                r#type: "color",
+                                value: bottom,
+                                oninput: move |event| {
+                                    if let LetterboxFill::Gradient(top, _) = letterbox_fill() {
+                                        let fill = LetterboxFill::Gradient(top, event.value());
+                                        background::save_letterbox_fill(&fill);
+                                        letterbox_fill.set(fill);
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    if let LetterboxFill::Image(selected) = letterbox_fill() {
+                        div {
+                            style: "display: grid; grid-template-columns: repeat(3, 1fr); gap: 6px;",
+                            for (index, background) in background_images().into_iter().enumerate() {
+                                div {
+                                    key: "{index}",
+                                    style: format!("aspect-ratio: 16 / 9; border-radius: 6px; cursor: pointer; background-image: url({}); background-size: cover; background-position: center; border: 2px solid {};",
+                                        background.url,
+                                        if index == selected { "#8b5cf6" } else { "transparent" }
+                                    ),
+                                    onclick: move |_| {
+                                        let fill = LetterboxFill::Image(index);
+                                        background::save_letterbox_fill(&fill);
+                                        letterbox_fill.set(fill);
+                                    },
+                                }
+                            }
+                        }
+                        label {
+                            style: "cursor: pointer; color: #93c5fd;",
+                            "Add background image"
+                            input {
+                                r#type: "file",
+                                accept: "image/*",
+                                style: "display: none;",
+                                onchange: move |event| {
+                                    spawn(async move {
+                                        let Some(url) = image::read_data_url(&event).await else {
+                                            return;
+                                        };
+                                        // Shrink large photos so they fit in browser storage
+                                        let url = image::shrink_data_url(&url, 1920.0).await.unwrap_or(url);
+                                        if let Some(loaded) = image::load_image(&url) {
+                                            background_images.write().push(BackgroundImage { url, image: loaded });
+                                            report_result(background::save_background_images(&background_images.read()));
+
+                                            let fill = LetterboxFill::Image(background_images.read().len() - 1);
+                                            background::save_letterbox_fill(&fill);
+                                            letterbox_fill.set(fill);
+                                        }
+                                    });
+                                },
+                            }
+                        }
+                    }
+
+                    // Frame
+                    div {
+                        style: "font-weight: bold; margin-top: 8px;",
+                        "Frame"
+                    }
+                    label {
+                        style: "display: flex; align-items: center; gap: 4px;",
+                        input {
+                            r#type: "checkbox",
+                            checked: frame_style().enabled,
+                            onchange: move |event| {
+                                frame_style.write().enabled = event.checked();
+                                frame::save_frame_style(&frame_style());
+                            },
+                        }
+                        "Inset screen in a styled frame"
+                    }
+                    for (label, max, value, setter) in [
+                        ("Padding", "200", frame_style().padding, (|style: &mut FrameStyle, value: f64| style.padding = value) as fn(&mut FrameStyle, f64)),
+                        ("Corners", "48", frame_style().corner_radius, |style: &mut FrameStyle, value: f64| style.corner_radius = value),
+                        ("Shadow", "80", frame_style().shadow, |style: &mut FrameStyle, value: f64| style.shadow = value),
+                    ] {
+                        div {
+                            key: "{label}",
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            span {
+                                style: "width: 96px;",
+                                {format!("{}: {:.0}px", label, value)}
+                            }
+                            input {
+                                r#type: "range",
+                                min: "0",
+                                max: max,
+                                step: "1",
+                                value: format!("{}", value),
+                                disabled: !frame_style().enabled,
+                                style: "flex: 1; accent-color: #6366f1;",
+                                oninput: move |event| {
+                                    if let Ok(value) = event.value().parse::<f64>() {
+                                        setter(&mut frame_style.write(), value);
+                                        frame::save_frame_style(&frame_style());
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    label {
+                        style: "display: flex; align-items: center; gap: 4px;",
+                        input {
+                            r#type: "checkbox",
+                            checked: frame_style().window_chrome,
+                            disabled: !frame_style().enabled,
+                            onchange: move |event| {
+                                frame_style.write().window_chrome = event.checked();
+                                frame::save_frame_style(&frame_style());
+                            },
+                        }
+                        "Window title bar"
+                    }
+                }
+            }
+
+            // Scenes panel
+            if open_panel() == Some(Panel::Scenes) {
+                div {
+                    style: "position: absolute; top: 20px; right: 20px; z-index: 10; width: 280px; max-height: calc(100vh - 40px); overflow-y: auto; background-color: rgba(31, 41, 55, 0.9); padding: 12px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); color: white; font-family: monospace; font-size: 12px; display: flex; flex-direction: column; gap: 8px;",
+                    div {
+                        style: "font-size: 14px; font-weight: bold;",
+                        "Scenes"
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        "A scene saves the layout, camera, crop, redactions and frame. Click or press its key to switch to it."
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        {format!("Keys {} are used by the recorder.", hotkey::reserved_list())}
+                    }
+
+                    for (index, item) in scenes().into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            style: format!("display: flex; flex-direction: column; gap: 6px; padding: 8px; background-color: rgba(55, 65, 81, 0.9); border-radius: 8px; border: 2px solid {};",
+                                if active_scene() == Some(index) { "#8b5cf6" } else { "transparent" }
+                            ),
+                            div {
+                                style: "display: flex; gap: 6px;",
+                                input {
+                                    r#type: "text",
+                                    value: item.name.clone(),
+                                    style: "flex: 1; min-width: 0;",
+                                    oninput: move |event| {
+                                        if let Some(scene) = scenes.write().get_mut(index) {
+                                            scene.name = event.value();
+                                        }
+                                        report_result(scene::save_scenes(&scenes.read()));
+                                    },
+                                }
+                                input {
+                                    r#type: "text",
+                                    title: "Hotkey",
+                                    maxlength: "1",
+                                    value: item.key.clone(),
+                                    style: "width: 24px; text-align: center;",
+                                    oninput: move |event| {
+                                        let key = event.value();
+                                        if let Err(error) = hotkey::check_binding(&key) {
+                                            report_error(error);
+                                            return;
+                                        }
+                                        if let Some(scene) = scenes.write().get_mut(index) {
+                                            scene.key = key;
+                                        }
+                                        report_result(scene::save_scenes(&scenes.read()));
+                                    },
+                                }
+                            }
+                            div {
+                                style: "display: flex; align-items: center; gap: 6px;",
+                                select {
+                                    value: item.transition.label(),
+                                    onchange: move |event| {
+                                        if let Some(transition) = SceneTransition::from_label(&event.value()) {
+                                            if let Some(scene) = scenes.write().get_mut(index) {
+                                                scene.transition = transition;
+                                            }
+                                            report_result(scene::save_scenes(&scenes.read()));
+                                        }
+                                    },
+                                    for transition in SceneTransition::ALL {
+                                        option { value: transition.label(), {transition.label()} }
+                                    }
+                                }
+                                span {
+                                    style: "flex: 1; color: #9ca3af; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                                    {item.layout.label()}
+                                }
+                            }
+                            div {
+                                style: "display: flex; align-items: center; gap: 6px;",
+                                button {
+                                    onclick: move |_| recall_scene(index),
+                                    "Show"
+                                }
+                                button {
+                                    title: "Replace with the current composition",
+                                    onclick: move |_| {
+                                        let mut all_scenes = scenes();
+                                        if let Some(scene) = all_scenes.get_mut(index) {
+                                            *scene = current_scene(scene.name.clone(), scene.key.clone(), scene.transition);
+                                        }
+                                        report_result(scene::save_scenes(&all_scenes));
+                                        scenes.set(all_scenes);
+                                    },
+                                    "Update"
+                                }
+                                div { style: "flex: 1;" }
+                                button {
+                                    title: "Move up",
+                                    disabled: index == 0,
+                                    onclick: move |_| {
+                                        let mut all_scenes = scenes();
+                                        if index > 0 && index < all_scenes.len() {
+                                            all_scenes.swap(index - 1, index);
+                                            if active_scene() == Some(index) {
+                                                active_scene.set(Some(index - 1));
+                                            } else if active_scene() == Some(index - 1) {
+                                                active_scene.set(Some(index));
+                                            }
+                                        }
+                                        report_result(scene::save_scenes(&all_scenes));
+                                        scenes.set(all_scenes);
+                                    },
+                                    "↑"
+                                }
+                                button {
+                                    title: "Move down",
+                                    disabled: index + 1 >= scenes.read().len(),
+                                    onclick: move |_| {
+                                        let mut all_scenes = scenes();
+                                        if index + 1 < all_scenes.len() {
+                                            all_scenes.swap(index, index + 1);
+                                            if active_scene() == Some(index) {
+                                                active_scene.set(Some(index + 1));
+                                            } else if active_scene() == Some(index + 1) {
+                                                active_scene.set(Some(index));
+                                            }
+                                        }
+                                        report_result(scene::save_scenes(&all_scenes));
+                                        scenes.set(all_scenes);
+                                    },
+                                    "↓"
+                                }
+                                button {
+                                    style: "background: none; color: #f87171; border: none; cursor: pointer;",
+                                    onclick: move |_| {
+                                        let mut all_scenes = scenes();
+                                        if index < all_scenes.len() {
+                                            all_scenes.remove(index);
+                                        }
+                                        report_result(scene::save_scenes(&all_scenes));
+                                        scenes.set(all_scenes);
+                                        active_scene.set(None);
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+
+                    button {
+                        onclick: move |_| {
+                            let name = format!("Scene {}", scenes.read().len() + 1);
+                            let new_scene = current_scene(name, String::new(), SceneTransition::Fade);
+                            scenes.write().push(new_scene);
+                            report_result(scene::save_scenes(&scenes.read()));
+                            active_scene.set(Some(scenes.read().len() - 1));
+                        },
+                        "+ Save current as scene"
+                    }
+                }
+            }
+
+            // Curtain panel
+            if open_panel() == Some(Panel::Curtains) {
+                div {
+                    style: "position: absolute; top: 20px; right: 20px; z-index: 10; width: 280px; max-height: calc(100vh - 40px); overflow-y: auto; background-color: rgba(31, 41, 55, 0.9); padding: 12px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); color: white; font-family: monospace; font-size: 12px; display: flex; flex-direction: column; gap: 8px;",
+                    div {
+                        style: "font-size: 14px; font-weight: bold;",
+                        "Curtains"
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        "Press a curtain's key to cover the recording. Recording continues underneath."
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        {format!("Keys {} are used by the recorder.", hotkey::reserved_list())}
+                    }
+
+                    for (index, item) in curtains().into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            style: format!("display: flex; flex-direction: column; gap: 6px; padding: 8px; background-color: rgba(55, 65, 81, 0.9); border-radius: 8px; border: 2px solid {};",
+                                if active_curtain() == Some(index) { "#f59e0b" } else { "transparent" }
+                            ),
+                            div {
+                                style: "display: flex; gap: 6px;",
+                                input {
+                                    r#type: "text",
+                                    value: item.name.clone(),
+                                    style: "flex: 1; min-width: 0;",
+                                    oninput: move |event| {
+                                        if let Some(curtain) = curtains.write().get_mut(index) {
+                                            curtain.name = event.value();
+                                        }
+                                        report_result(curtain::save_curtains(&curtains.read()));
+                                    },
+                                }
+                                input {
+                                    r#type: "text",
+                                    title: "Hotkey",
+                                    maxlength: "1",
+                                    value: item.key.clone(),
+                                    style: "width: 24px; text-align: center;",
+                                    oninput: move |event| {
+                                        let key = event.value();
+                                        if let Err(error) = hotkey::check_binding(&key) {
+                                            report_error(error);
+                                            return;
+                                        }
+                                        if let Some(curtain) = curtains.write().get_mut(index) {
+                                            curtain.key = key;
+                                        }
+                                        report_result(curtain::save_curtains(&curtains.read()));
+                                    },
+                                }
+                            }
+                            input {
+                                r#type: "text",
+                                placeholder: "Slate text",
+                                value: item.text.clone(),
+                                oninput: move |event| {
+                                    if let Some(curtain) = curtains.write().get_mut(index) {
+                                        curtain.text = event.value();
+                                    }
+                                    report_result(curtain::save_curtains(&curtains.read()));
+                                },
+                            }
+                            div {
+                                style: "display: flex; align-items: center; gap: 6px;",
+                                input {
+                                    r#type: "color",
+                                    value: item.color.clone(),
+                                    oninput: move |event| {
+                                        if let Some(curtain) = curtains.write().get_mut(index) {
+                                            curtain.color = event.value();
+                                        }
+                                        report_result(curtain::save_curtains(&curtains.read()));
+                                    },
+                                }
+                                label {
+                                    style: "flex: 1; cursor: pointer; color: #93c5fd;",
+                                    {if item.image.is_some() { "Change image" } else { "Add image" }}
+                                    input {
+                                        r#type: "file",
+                                        accept: "image/*",
+                                        style: "display: none;",
+                                        onchange: move |event| {
+                                            spawn(async move {
+                                                if let Some(url) = image::read_data_url(&event).await {
+                                                    if let Some(curtain) = curtains.write().get_mut(index) {
+                                                        curtain.set_image(Some(url));
+                                                    }
+                                                    report_result(curtain::save_curtains(&curtains.read()));
+                                                }
+                                            });
+                                        },
+                                    }
+                                }
+                                if item.image.is_some() {
+                                    button {
+                                        onclick: move |_| {
+                                            if let Some(curtain) = curtains.write().get_mut(index) {
+                                                curtain.set_image(None);
+                                            }
+                                            report_result(curtain::save_curtains(&curtains.read()));
+                                        },
+                                        "No image"
+                                    }
+                                }
+                            }
+                            div {
+                                style: "display: flex; align-items: center; gap: 6px;",
+                                label {
+                                    style: "flex: 1; display: flex; align-items: center; gap: 4px;",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: item.mute_mic,
+                                        onchange: move |event| {
+                                            if let Some(curtain) = curtains.write().get_mut(index) {
+                                                curtain.mute_mic = event.checked();
+                                            }
+                                            report_result(curtain::save_curtains(&curtains.read()));
+                                        },
+                                    }
+                                    "Mute mic"
+                                }
+                                button {
+                                    onclick: move |_| toggle_curtain(index),
+                                    {if active_curtain() == Some(index) { "Hide" } else { "Show" }}
+                                }
+                                button {
+                                    style: "background: none; color: #f87171; border: none; cursor: pointer;",
+                                    onclick: move |_| {
+                                        if index < curtains.read().len() {
+                                            curtains.write().remove(index);
+                                        }
+                                        report_result(curtain::save_curtains(&curtains.read()));
+                                        active_curtain.set(None);
+                                        shown_curtain.set(None);
+                                        curtain_fade.set(Tween::settled(0.0));
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+
+                    button {
+                        onclick: move |_| {
+                            curtains.write().push(Curtain::new("New curtain", "", "", "#111827", false));
+                            report_result(curtain::save_curtains(&curtains.read()));
+                        },
+                        "+ Add curtain"
+                    }
+                }
+            }
+
+            // Redaction panel
+            if is_redact_mode() {
+                div {
+                    style: "position: absolute; top: 20px; right: 20px; z-index: 10; width: 260px; max-height: calc(100vh - 40px); overflow-y: auto; background-color: rgba(31, 41, 55, 0.9); padding: 12px; border-radius: 12px; box-shadow: 0 2px 8px rgba(0,0,0,0.3); color: white; font-family: monospace; font-size: 12px; display: flex; flex-direction: column; gap: 8px;",
+                    div {
+                        style: "font-size: 14px; font-weight: bold;",
+                        "Redaction"
+                    }
+                    div {
+                        style: "color: #9ca3af;",
+                        "Drag over the screen share to hide a region."
+                    }
+                    div {
+                        style: "display: flex; align-items: center; justify-content: space-between; gap: 8px;",
+                        "New regions:"
+                        select {
+                            value: redact_style().label(),
+                            onchange: move |event| {
+                                if let Some(style) = RedactStyle::from_label(&event.value()) {
+                                    redact_style.set(style);
+                                }
+                            },
+                            for style in RedactStyle::ALL {
+                                option { value: style.label(), {style.label()} }
+                            }
+                        }
+                    }
+
+                    for (index, region) in redactions().into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            style: "display: flex; align-items: center; gap: 6px; padding: 4px 6px; background-color: rgba(55, 65, 81, 0.9); border-radius: 6px;",
+                            span {
+                                style: "flex: 1;",
+                                {format!("#{} {:.0}×{:.0}", index + 1, region.width, region.height)}
+                            }
+                            select {
+                                value: region.style.label(),
+                                onchange: move |event| {
+                                    if let Some(style) = RedactStyle::from_label(&event.value()) {
+                                        if let Some(region) = redactions.write().get_mut(index) {
+                                            region.style = style;
+                                        }
+                                    }
+                                },
+                                for style in RedactStyle::ALL {
+                                    option { value: style.label(), {style.label()} }
+                                }
+                            }
+                            button {
+                                style: "background: none; color: #f87171; border: none; cursor: pointer;",
+                                onclick: move |_| {
+                                    if index < redactions.read().len() {
+                                        redactions.write().remove(index);
+                                    }
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+
+                    // Presets
+                    div {
+                        style: "font-size: 14px; font-weight: bold; margin-top: 8px;",
+                        "Presets"
+                    }
+                    div {
+                        style: "display: flex; gap: 6px;",
+                        input {
+                            r#type: "text",
+                            placeholder: "Preset name",
+                            value: preset_name(),
+                            style: "flex: 1; min-width: 0;",
+                            oninput: move |event| preset_name.set(event.value()),
+                        }
+                        button {
+                            disabled: preset_name().trim().is_empty(),
+                            onclick: move |_| {
+                                let name = preset_name().trim().to_string();
+                                let mut all_presets = presets();
+                                match all_presets.iter_mut().find(|preset| preset.name == name) {
+                                    Some(existing) => existing.redactions = redactions(),
+                                    None => all_presets.push(preset::Preset {
+                                        name,
+                                        redactions: redactions(),
+                                    }),
+                                }
+                                preset::save_presets(&all_presets);
+                                presets.set(all_presets);
+                                preset_name.set(String::new());
+                            },
+                            "Save"
+                        }
+                    }
+                    for (index, saved) in presets().into_iter().enumerate() {
+                        div {
+                            key: "{saved.name}",
+                            style: "display: flex; align-items: center; gap: 6px;",
+                            span {
+                                style: "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                                {saved.name.clone()}
+                            }
+                            button {
+                                onclick: move |_| {
+                                    redactions.set(saved.redactions.clone());
+                                },
+                                "Load"
+                            }
+                            button {
+                                style: "background: none; color: #f87171; border: none; cursor: pointer;",
+                                onclick: move |_| {
+                                    let mut all_presets = presets();
+                                    if index < all_presets.len() {
+                                        all_presets.remove(index);
+                                    }
+                                    preset::save_presets(&all_presets);
+                                    presets.set(all_presets);
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Readiness check before recording
+            if show_readiness() {
+                ReadinessPanel {
+                    show_readiness,
+                    show_capabilities,
+                    mic_stream,
+                    camera_stream,
+                    is_screen_sharing,
+                    camera_enabled,
+                    mic_permission,
+                    camera_permission,
+                    input_devices,
+                    mic_level,
+                    on_allow: move |device| match device {
+                        Device::Microphone => start_microphone(),
+                        Device::Screen => start_screen_share(),
+                        Device::Camera => start_camera(),
+                    },
+                    on_record: move |_| start_recording(),
+                }
+            }
+
+            // Review of the take just recorded
+            if review.read().is_some() {
+                ReviewPanel {
+                    review,
+                    review_position,
+                    is_review_playing,
+                    is_saving_review,
+                    on_discard: move |_| discard_review(),
+                    on_retake: move |_| retake(),
+                    on_save: move |_| save_review(),
+                }
+            }
+
+            // Recordings kept in the browser
+            if show_library() {
+                LibraryPanel {
+                    show_library,
+                    library_recordings,
+                    selected_recordings,
+                    is_joining,
+                    storage_estimate,
+                    playing_recording,
+                    on_close_player: move |_| close_player(),
+                    on_play: play_recording,
+                    on_download: download_recording,
+                    on_download_chapters: move |(saved, youtube)| download_chapters(saved, youtube),
+                    on_rename: move |(id, name): (String, String)| {
+                        report_result(library::rename(&id, &name));
+                        library_recordings.set(library::load_recordings());
+                    },
+                    on_delete: delete_recording,
+                    on_join: move |_| join_recordings(),
+                }
+            }
+
+            // Recordings cut short by a crash or a closed tab
+            if !unfinished_sessions().is_empty() {
+                div {
+                    style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.7); z-index: 850; display: flex; align-items: center; justify-content: center;",
+                    div {
+                        style: "background-color: #1f2937; color: white; padding: 24px; border-radius: 16px; width: 520px; max-height: 90vh; overflow-y: auto; display: flex; flex-direction: column; gap: 12px; font-family: system-ui, sans-serif; font-size: 13px; box-shadow: 0 20px 25px -5px rgba(0, 0, 0, 0.5);",
+                        h2 {
+                            style: "margin: 0; font-size: 20px;",
+                            "Unfinished recordings"
+                        }
+                        p {
+                            style: "margin: 0; color: #9ca3af;",
+                            "These recordings were interrupted before they were saved. Recover them to save what was recorded, or discard them."
+                        }
+                        for unfinished in unfinished_sessions() {
+                            div {
+                                key: "{unfinished.id}",
+                                style: "display: flex; align-items: center; gap: 8px; border-top: 1px solid #374151; padding-top: 12px;",
+                                div {
+                                    style: "flex: 1; display: flex; flex-direction: column; gap: 2px;",
+                                    strong {
+                                        {String::from(js_sys::Date::new(&unfinished.started_at.into()).to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED))}
+                                    }
+                                    span {
+                                        style: "color: #9ca3af; font-size: 11px;",
+                                        {format!("About {} s, {:.1} MB", unfinished.chunk_count as i32 * session::CHUNK_MS / 1000, unfinished.size / 1_000_000.0)}
+                                    }
+                                }
+                                if recovering_session().as_ref() == Some(&unfinished.id) {
+                                    span { style: "color: #9ca3af;", "Recovering…" }
+                                } else {
+                                    button {
+                                        disabled: recovering_session().is_some(),
+                                        onclick: {
+                                            let unfinished = unfinished.clone();
+                                            move |_| recover_session(unfinished.clone())
+                                        },
+                                        "Recover"
+                                    }
+                                    button {
+                                        disabled: recovering_session().is_some(),
+                                        onclick: {
+                                            let id = unfinished.id.clone();
+                                            move |_| discard_session(id.clone())
+                                        },
+                                        "Discard"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Error toasts
+            if !notices.read().is_empty() {
+                div {
+                    style: "position: fixed; top: 20px; right: 20px; z-index: 900; display: flex; flex-direction: column; gap: 8px; width: 340px;",
+                    for (id, error) in notices() {
+                        div {
+                            key: "{id}",
+                            style: format!("background-color: #1f2937; color: white; padding: 12px 14px; border-radius: 10px; border-left: 4px solid {}; box-shadow: 0 4px 12px rgba(0,0,0,0.4); font-family: system-ui, sans-serif; font-size: 13px;",
+                                if error.is_cancellation() { "#6b7280" } else { "#ef4444" }
+                            ),
+                            div {
+                                style: "display: flex; gap: 8px; align-items: flex-start;",
+                                div {
+                                    style: "flex: 1; font-weight: 600;",
+                                    "{error}"
+                                }
+                                button {
+                                    style: "background: none; border: none; color: #9ca3af; cursor: pointer; font-size: 14px; padding: 0;",
+                                    onclick: move |_| notices.write().retain(|(notice_id, _)| *notice_id != id),
+                                    "✕"
+                                }
+                            }
+                            div {
+                                style: "margin-top: 4px; color: #d1d5db; line-height: 1.4;",
+                                "{error.hint()}"
+                            }
+                            button {
+                                style: "margin-top: 6px; background: none; border: none; color: #93c5fd; cursor: pointer; font-size: 12px; padding: 0;",
+                                onclick: move |_| show_diagnostics.set(true),
+                                "Diagnostics log"
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Diagnostics log
+            if show_diagnostics() {
+                div {
+                    style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.6); z-index: 950; display: flex; align-items: center; justify-content: center;",
+                    onclick: move |_| show_diagnostics.set(false),
+                    div {
+                        style: "background-color: #1f2937; color: white; padding: 20px; border-radius: 12px; width: 560px; max-height: 70vh; display: flex; flex-direction: column; gap: 12px; font-family: system-ui, sans-serif; font-size: 13px;",
+                        onclick: move |event| event.stop_propagation(),
+                        div {
+                            style: "display: flex; align-items: center; gap: 8px;",
+                            h3 {
+                                style: "margin: 0; flex: 1; font-size: 16px;",
+                                "Diagnostics log"
+                            }
+                            button {
+                                onclick: move |_| {
+                                    error::clear_diagnostics();
+                                    show_diagnostics.set(false);
+                                },
+                                "Clear"
+                            }
+                            button {
+                                onclick: move |_| show_diagnostics.set(false),
+                                "Close"
+                            }
+                        }
+                        div {
+                            style: "overflow-y: auto; display: flex; flex-direction: column; gap: 8px;",
+                            for entry in error::load_diagnostics().into_iter().rev() {
+                                div {
+                                    style: "border-bottom: 1px solid #374151; padding-bottom: 8px;",
+                                    div {
+                                        style: "color: #9ca3af; font-size: 11px;",
+                                        {String::from(js_sys::Date::new(&entry.time.into()).to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED))}
+                                    }
+                                    div { "{entry.error}" }
+                                    div {
+                                        style: "color: #d1d5db;",
+                                        "{entry.error.hint()}"
+                                    }
+                                    div {
+                                        style: "color: #6b7280; font-size: 11px; word-break: break-all;",
+                                        "{entry.user_agent}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Browser capability report
+            if show_capabilities() {
+                div {
+                    style: "position: fixed; top: 0; left: 0; width: 100vw; height: 100vh; background-color: rgba(0, 0, 0, 0.8); z-index: 1000; display: flex; align-items: center; justify-content: center;",
+                    onclick: move |_| show_capabilities.set(false),
+                    div {
+                        style: "background-color: #1f2937; color: white; padding: 24px; border-radius: 16px; width: 560px; max-height: 90vh; overflow-y: auto; display: flex; flex-direction: column; gap: 12px; font-family: system-ui, sans-serif; font-size: 13px; box-shadow: 0 20px 25px -5px rgba(0, 0, 0, 0.5);",
+                        onclick: move |event| event.stop_propagation(),
+                        h2 {
+                            style: format!("margin: 0; font-size: 20px; color: {};", if capabilities().can_record() { "white" } else { "#f59e0b" }),
+                            {if capabilities().can_record() { "Browser capabilities" } else { "⚠️ This browser can't record" }}
+                        }
+                        for (name, supported, without) in capabilities().report() {
+                            div {
+                                key: "{name}",
+                                style: "display: flex; gap: 10px; align-items: baseline; border-top: 1px solid #374151; padding-top: 8px;",
+                                span {
+                                    style: format!("width: 16px; color: {};", if supported { "#10b981" } else { "#ef4444" }),
+                                    {if supported { "✓" } else { "✕" }}
+                                }
+                                div {
+                                    style: "flex: 1;",
+                                    div { "{name}" }
+                                    if !supported {
+                                        div {
+                                            style: "color: #9ca3af;",
+                                            "{without}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(mime_type) = capabilities().recording_mime_type() {
+                            div {
+                                style: "color: #9ca3af; border-top: 1px solid #374151; padding-top: 8px;",
+                                "Recording as {mime_type}"
+                            }
+                        }
+                        button {
+                            style: "align-self: flex-end; background-color: #6b7280; color: white; border: none; padding: 10px 20px; border-radius: 8px; cursor: pointer; font-weight: 600;",
+                            onclick: move |_| show_capabilities.set(false),
+                            "Close"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Lifecycle of a recording, kept free of browser types so the transitions can
+//! be tested natively.
+
+/// Seconds counted down before the recorder starts
+pub const COUNTDOWN_SECONDS: u32 = 3;
+
+/// Where a recording is in its lifecycle
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum RecordingState {
+    #[default]
+    Idle,
+    /// Counting down to the start, 0 while the recorder is being started
+    Countdown { remaining: u32 },
+    Recording,
+    Paused,
+    /// Stopped, waiting for the recorder to hand over the last data
+    Finalizing,
+    /// The take is shown for saving or discarding
+    Reviewing,
+    Saved,
+    Failed(String),
+}
+
+/// Something that happened to the recording, from the UI or the recorder
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordingEvent {
+    /// The user asked for a new recording
+    Start,
+    /// One second of the countdown passed
+    Tick,
+    /// The recorder is running
+    Started,
+    Pause,
+    Resume,
+    /// The user (or closing picture-in-picture) stopped the recording; cancels a countdown
+    Stop,
+    /// The recording was assembled and shown for review
+    Review,
+    /// The recording was saved or handed over
+    Finished,
+    /// The reviewed take was thrown away
+    Discard,
+    Fail(String),
+}
+
+impl RecordingState {
+    /// State after an event, None if the event isn't allowed now (a second
+    /// start while recording, a stop while idle, ...)
+    pub fn next(&self, event: &RecordingEvent) -> Option<RecordingState> {
+        use RecordingEvent as Event;
+        use RecordingState as State;
+
+        match (self, event) {
+            (State::Idle | State::Saved | State::Failed(_), Event::Start) => Some(State::Countdown {
+                remaining: COUNTDOWN_SECONDS,
+            }),
+            (State::Countdown { remaining }, Event::Tick) if *remaining > 0 => Some(State::Countdown {
+                remaining: remaining - 1,
+            }),
+            (State::Countdown { remaining: 0 }, Event::Started) => Some(State::Recording),
+            (State::Countdown { .. }, Event::Stop) => Some(State::Idle),
+            (State::Recording, Event::Pause) => Some(State::Paused),
+            (State::Paused, Event::Resume) => Some(State::Recording),
+            (State::Recording | State::Paused, Event::Stop) => Some(State::Finalizing),
+            (State::Finalizing, Event::Review) => Some(State::Reviewing),
+            (State::Finalizing | State::Reviewing, Event::Finished) => Some(State::Saved),
+            (State::Reviewing, Event::Discard) => Some(State::Idle),
+            (State::Countdown { .. } | State::Recording | State::Paused | State::Finalizing, Event::Fail(message)) => {
+                Some(State::Failed(message.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a recording is under way, from the countdown until it is saved
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self,
+            RecordingState::Countdown { .. }
+                | RecordingState::Recording
+                | RecordingState::Paused
+                | RecordingState::Finalizing
+        )
+    }
+
+    /// Whether the recorder is running, possibly paused
+    pub fn is_capturing(&self) -> bool {
+        matches!(self, RecordingState::Recording | RecordingState::Paused)
+    }
+
+    /// Countdown number to show, 0 for none
+    pub fn countdown(&self) -> u32 {
+        match self {
+            RecordingState::Countdown { remaining } => *remaining,
+            _ => 0,
+        }
+    }
+}
+
+/// Time spent recording, leaving out pauses and the countdown
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RecordingClock {
+    elapsed_ms: f64,
+    /// When recording last started or resumed
+    running_since: Option<f64>,
+}
+
+impl RecordingClock {
+    /// Follow the recording into a new state at `now` (milliseconds)
+    pub fn update(self, state: &RecordingState, now: f64) -> Self {
+        match (state, self.running_since) {
+            (RecordingState::Countdown { .. }, _) => Self::default(),
+            (RecordingState::Recording, None) => Self {
+                running_since: Some(now),
+                ..self
+            },
+            (RecordingState::Recording, Some(_)) => self,
+            (_, Some(since)) => Self {
+                elapsed_ms: self.elapsed_ms + (now - since).max(0.0),
+                running_since: None,
+            },
+            (_, None) => self,
+        }
+    }
+
+    /// Milliseconds recorded as of `now`
+    pub fn elapsed_ms(&self, now: f64) -> f64 {
+        self.elapsed_ms + self.running_since.map_or(0.0, |since| (now - since).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Apply events in order, panicking on the first one that isn't allowed
+    fn run(events: &[RecordingEvent]) -> RecordingState {
+        events.iter().fold(RecordingState::Idle, |state, event| {
+            state
+                .next(event)
+                .unwrap_or_else(|| panic!("{event:?} not allowed in {state:?}"))
+        })
+    }
+
+    fn started() -> Vec<RecordingEvent> {
+        let mut events = vec![RecordingEvent::Start];
+        events.extend((0..COUNTDOWN_SECONDS).map(|_| RecordingEvent::Tick));
+        events.push(RecordingEvent::Started);
+        events
+    }
+
+    #[test]
+    fn full_recording() {
+        let mut events = started();
+        events.extend([
+            RecordingEvent::Pause,
+            RecordingEvent::Resume,
+            RecordingEvent::Stop,
+            RecordingEvent::Finished,
+        ]);
+        assert_eq!(run(&events), RecordingState::Saved);
+    }
+
+    #[test]
+    fn countdown_counts_down_to_zero() {
+        let state = run(&[RecordingEvent::Start]);
+        assert_eq!(state.countdown(), COUNTDOWN_SECONDS);
+        let state = run(&started()[..=COUNTDOWN_SECONDS as usize]);
+        assert_eq!(state, RecordingState::Countdown { remaining: 0 });
+        assert_eq!(state.next(&RecordingEvent::Tick), None);
+    }
+
+    #[test]
+    fn recorder_cannot_start_mid_countdown() {
+        let state = run(&[RecordingEvent::Start, RecordingEvent::Tick]);
+        assert_eq!(state.next(&RecordingEvent::Started), None);
+    }
+
+    #[test]
+    fn no_double_start() {
+        let countdown = run(&[RecordingEvent::Start]);
+        assert_eq!(countdown.next(&RecordingEvent::Start), None);
+        assert_eq!(run(&started()).next(&RecordingEvent::Start), None);
+        let finalizing = run(&[started(), vec![RecordingEvent::Stop]].concat());
+        assert_eq!(finalizing.next(&RecordingEvent::Start), None);
+    }
+
+    #[test]
+    fn no_double_stop() {
+        let finalizing = run(&[started(), vec![RecordingEvent::Stop]].concat());
+        assert_eq!(finalizing, RecordingState::Finalizing);
+        assert_eq!(finalizing.next(&RecordingEvent::Stop), None);
+        assert_eq!(RecordingState::Idle.next(&RecordingEvent::Stop), None);
+        assert_eq!(RecordingState::Saved.next(&RecordingEvent::Stop), None);
+    }
+
+    #[test]
+    fn stop_cancels_countdown() {
+        let state = run(&[RecordingEvent::Start, RecordingEvent::Tick, RecordingEvent::Stop]);
+        assert_eq!(state, RecordingState::Idle);
+        assert!(!state.is_busy());
+    }
+
+    #[test]
+    fn pause_only_while_recording() {
+        assert_eq!(RecordingState::Idle.next(&RecordingEvent::Pause), None);
+        assert_eq!(RecordingState::Paused.next(&RecordingEvent::Pause), None);
+        assert_eq!(RecordingState::Recording.next(&RecordingEvent::Resume), None);
+        assert!(RecordingState::Paused.is_capturing());
+    }
+
+    #[test]
+    fn failure_allows_a_new_start() {
+        let failed = run(&[started(), vec![RecordingEvent::Fail("no data".into())]].concat());
+        assert_eq!(failed, RecordingState::Failed("no data".into()));
+        assert!(!failed.is_busy());
+        assert_eq!(
+            failed.next(&RecordingEvent::Start),
+            Some(RecordingState::Countdown {
+                remaining: COUNTDOWN_SECONDS
+            })
+        );
+    }
+
+    #[test]
+    fn cannot_fail_when_not_busy() {
+        assert_eq!(RecordingState::Idle.next(&RecordingEvent::Fail("late".into())), None);
+        assert_eq!(RecordingState::Saved.next(&RecordingEvent::Fail("late".into())), None);
+    }
+
+    #[test]
+    fn finished_only_after_stop() {
+        assert_eq!(RecordingState::Recording.next(&RecordingEvent::Finished), None);
+        assert_eq!(RecordingState::Idle.next(&RecordingEvent::Finished), None);
+    }
+
+    #[test]
+    fn review_ends_in_save_or_discard() {
+        let reviewing = run(&[started(), vec![RecordingEvent::Stop, RecordingEvent::Review]].concat());
+        assert_eq!(reviewing, RecordingState::Reviewing);
+        assert!(!reviewing.is_busy());
+        // A retake throws the take away before starting again
+        assert_eq!(reviewing.next(&RecordingEvent::Start), None);
+        assert_eq!(reviewing.next(&RecordingEvent::Discard), Some(RecordingState::Idle));
+        assert_eq!(reviewing.next(&RecordingEvent::Finished), Some(RecordingState::Saved));
+        assert_eq!(RecordingState::Finalizing.next(&RecordingEvent::Discard), None);
+    }
+
+    #[test]
+    fn clock_leaves_out_pauses() {
+        let clock = RecordingClock::default()
+            .update(&RecordingState::Countdown { remaining: 0 }, 0.0)
+            .update(&RecordingState::Recording, 1000.0)
+            .update(&RecordingState::Paused, 3000.0);
+        assert_eq!(clock.elapsed_ms(10_000.0), 2000.0);
+        let clock = clock.update(&RecordingState::Recording, 10_000.0);
+        assert_eq!(clock.elapsed_ms(10_500.0), 2500.0);
+        let clock = clock.update(&RecordingState::Finalizing, 11_000.0);
+        assert_eq!(clock.elapsed_ms(20_000.0), 3000.0);
+    }
+
+    #[test]
+    fn clock_restarts_with_the_countdown() {
+        let clock = RecordingClock::default()
+            .update(&RecordingState::Recording, 0.0)
+            .update(&RecordingState::Saved, 5000.0)
+            .update(&RecordingState::Countdown { remaining: 3 }, 6000.0);
+        assert_eq!(clock.elapsed_ms(9000.0), 0.0);
+    }
+}
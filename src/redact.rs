@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::screen::{ScreenSource, ScreenTransform};
+
+// Size of one pixelation block on the canvas
+const PIXEL_BLOCK_SIZE: f64 = 14.0;
+// Blur radius on the canvas
+const BLUR_RADIUS: f64 = 18.0;
+
+// x, y, width and height
+type Rect = (f64, f64, f64, f64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedactStyle {
+    Pixelate,
+    Blur,
+    Solid,
+}
+
+impl RedactStyle {
+    pub const ALL: [RedactStyle; 3] = [RedactStyle::Pixelate, RedactStyle::Blur, RedactStyle::Solid];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RedactStyle::Pixelate => "Pixelate",
+            RedactStyle::Blur => "Blur",
+            RedactStyle::Solid => "Solid",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.label() == label)
+    }
+}
+
+/// A region of the screen share hidden in every recorded frame. Coordinates
+/// are in screen video pixels so the region stays on the same content when
+/// the screen is cropped or rescaled.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RedactionRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub style: RedactStyle,
+}
+
+impl RedactionRegion {
+    /// Intersect with a source rectangle, returning None if nothing is left
+    fn clip_to(&self, src: Rect) -> Option<Rect> {
+        let left = self.x.max(src.0);
+        let top = self.y.max(src.1);
+        let right = (self.x + self.width).min(src.0 + src.2);
+        let bottom = (self.y + self.height).min(src.1 + src.3);
+
+        if right > left && bottom > top {
+            Some((left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+}
+
+/// Draw `src` of a source blurred into `dest`, `draw_source` drawing a source
+/// rectangle into a destination one. A slightly larger area is blurred, as far
+/// as the source's `bounds` allow, so the edges don't fade to transparent.
+pub fn draw_blurred(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    radius: f64,
+    bounds: (f64, f64),
+    src: Rect,
+    dest: Rect,
+    draw_source: &dyn Fn(&web_sys::CanvasRenderingContext2d, Rect, Rect),
+) {
+    let (src_x, src_y, src_width, src_height) = src;
+    let (x, y, width, height) = dest;
+    if src_width <= 0.0 || src_height <= 0.0 {
+        return;
+    }
+    let (scale_x, scale_y) = (width / src_width, height / src_height);
+    let margin = radius * 2.0;
+    let grow_x = (src_x - margin / scale_x).max(0.0);
+    let grow_y = (src_y - margin / scale_y).max(0.0);
+    let grow_width = (src_x + src_width + margin / scale_x).min(bounds.0) - grow_x;
+    let grow_height = (src_y + src_height + margin / scale_y).min(bounds.1) - grow_y;
+
+    ctx.save();
+    ctx.set_filter(&format!("blur({}px)", radius));
+    draw_source(
+        ctx,
+        (grow_x, grow_y, grow_width, grow_height),
+        (
+            x + (grow_x - src_x) * scale_x,
+            y + (grow_y - src_y) * scale_y,
+            grow_width * scale_x,
+            grow_height * scale_y,
+        ),
+    );
+    ctx.restore();
+}
+
+/// Draw `src` of a source into `dest` in blocks of `block_size` canvas pixels,
+/// `draw_source` drawing a source rectangle into a destination one
+pub fn draw_pixelated(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    scratch: &web_sys::HtmlCanvasElement,
+    block_size: f64,
+    src: Rect,
+    dest: Rect,
+    draw_source: &dyn Fn(&web_sys::CanvasRenderingContext2d, Rect, Rect),
+) {
+    let (x, y, width, height) = dest;
+    // Downscale into the scratch canvas, then scale back up without smoothing
+    let columns = (width / block_size).ceil().max(1.0);
+    let rows = (height / block_size).ceil().max(1.0);
+    scratch.set_width(columns as u32);
+    scratch.set_height(rows as u32);
+
+    let Ok(Some(context)) = scratch.get_context("2d") else {
+        return;
+    };
+    let Ok(scratch_ctx) = context.dyn_into::<web_sys::CanvasRenderingContext2d>() else {
+        return;
+    };
+    draw_source(&scratch_ctx, src, (0.0, 0.0, columns, rows));
+
+    ctx.save();
+    ctx.set_image_smoothing_enabled(false);
+    let _ = ctx.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+        scratch, 0.0, 0.0, columns, rows, x, y, width, height,
+    );
+    ctx.restore();
+}
+
+/// Draw all redaction regions over the already drawn screen share
+pub fn draw_redactions(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    source: &ScreenSource,
+    scratch: &web_sys::HtmlCanvasElement,
+    regions: &[RedactionRegion],
+    transform: &ScreenTransform,
+) {
+    let (video_width, video_height) = source.size();
+    let draw_source = |ctx: &web_sys::CanvasRenderingContext2d, src: Rect, dest: Rect| {
+        source.draw(ctx, src.0, src.1, src.2, src.3, dest.0, dest.1, dest.2, dest.3);
+    };
+
+    for region in regions {
+        // Only the part of the region inside the current crop is visible
+        let Some(src) = region.clip_to(transform.src) else {
+            continue;
+        };
+        let (x, y) = transform.to_canvas(src.0, src.1);
+        let (width, height) = (src.2 * transform.scale, src.3 * transform.scale);
+        let dest = (x, y, width, height);
+
+        match region.style {
+            RedactStyle::Solid => {
+                ctx.set_fill_style_str("black");
+                ctx.fill_rect(x, y, width, height);
+            }
+            RedactStyle::Blur => {
+                ctx.save();
+                ctx.begin_path();
+                ctx.rect(x, y, width, height);
+                ctx.clip();
+                draw_blurred(ctx, BLUR_RADIUS, (video_width, video_height), src, dest, &draw_source);
+                ctx.restore();
+            }
+            RedactStyle::Pixelate => draw_pixelated(ctx, scratch, PIXEL_BLOCK_SIZE, src, dest, &draw_source),
+        }
+    }
+}
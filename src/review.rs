@@ -0,0 +1,217 @@
+//! Looking over a take right after it stops, before deciding to keep it,
+//! retake it, or trim it and cut parts out.
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::capability;
+use crate::error::RecorderError;
+use crate::markers::{self, Marker};
+use crate::webm;
+
+/// Bars in the review waveform
+pub const WAVEFORM_BARS: usize = 160;
+
+/// Shortest part of a take that can be kept when trimming
+pub const MIN_TRIM_MS: f64 = 500.0;
+
+/// A finished take waiting for the keep or retake decision
+#[derive(Clone, Debug, PartialEq)]
+pub struct Review {
+    pub blob: web_sys::Blob,
+    /// Object URL the review player plays
+    pub url: String,
+    pub mime_type: String,
+    pub duration_ms: f64,
+    pub thumbnail: Option<String>,
+    /// Loudness of each bar from 0 to 1, empty until the audio is decoded
+    pub waveform: Vec<f32>,
+    /// Part to keep in milliseconds, set while trimming
+    pub trim: Option<(f64, f64)>,
+    /// Parts to take out of the middle, in milliseconds
+    pub cuts: Vec<(f64, f64)>,
+    /// Start of a cut whose end isn't set yet
+    pub cut_start: Option<f64>,
+    /// Dropped while recording, in milliseconds from the start of the take
+    pub markers: Vec<Marker>,
+}
+
+impl Review {
+    /// Trimming cuts the file without re-encoding, which only works for WebM
+    pub fn can_trim(&self) -> bool {
+        capability::file_extension(&self.mime_type) == "webm"
+    }
+
+    /// Whether saving changes the take
+    pub fn is_edited(&self) -> bool {
+        self.trim.is_some() || !self.cuts.is_empty()
+    }
+
+    /// Parts that are saved, in order
+    pub fn kept_ranges(&self) -> Vec<(f64, f64)> {
+        kept_ranges(self.trim.unwrap_or((0.0, self.duration_ms)), &self.cuts)
+    }
+
+    /// Length of the parts asked for; the saved take can be a little longer, as
+    /// each part starts at a keyframe
+    pub fn kept_ms(&self) -> f64 {
+        self.kept_ranges().iter().map(|(start, end)| end - start).sum()
+    }
+}
+
+/// Loudest sample in each of `bars` equal slices, scaled so the loudest bar is 1
+pub fn waveform(samples: &[f32], bars: usize) -> Vec<f32> {
+    if samples.is_empty() || bars == 0 {
+        return Vec::new();
+    }
+    let peaks: Vec<f32> = (0..bars)
+        .map(|bar| {
+            let from = bar * samples.len() / bars;
+            let to = ((bar + 1) * samples.len() / bars).max(from + 1).min(samples.len());
+            samples[from..to].iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+        })
+        .collect();
+    let loudest = peaks.iter().copied().fold(0.0f32, f32::max);
+    if loudest == 0.0 {
+        return peaks;
+    }
+    peaks.into_iter().map(|peak| peak / loudest).collect()
+}
+
+/// Keep a trim range inside the take and at least `MIN_TRIM_MS` long
+pub fn clamp_trim(start_ms: f64, end_ms: f64, duration_ms: f64) -> (f64, f64) {
+    let min = MIN_TRIM_MS.min(duration_ms);
+    let start = start_ms.clamp(0.0, (duration_ms - min).max(0.0));
+    let end = end_ms.clamp(start + min, duration_ms.max(start + min));
+    (start, end)
+}
+
+/// What is left of `keep` once `cuts` are taken out of it, in order
+pub fn kept_ranges(keep: (f64, f64), cuts: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut cuts: Vec<(f64, f64)> = cuts.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+    cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut ranges = Vec::new();
+    let mut from = keep.0;
+    for (start, end) in cuts {
+        if start > from {
+            ranges.push((from, start.min(keep.1)));
+        }
+        from = from.max(end);
+        if from >= keep.1 {
+            break;
+        }
+    }
+    if from < keep.1 {
+        ranges.push((from, keep.1));
+    }
+    ranges.retain(|(start, end)| end > start);
+    ranges
+}
+
+/// Where playback should jump to if `ms` falls inside a cut
+pub fn skip_cut(cuts: &[(f64, f64)], ms: f64) -> Option<f64> {
+    cuts.iter()
+        .find(|&&(start, end)| (start.min(end)..start.max(end)).contains(&ms))
+        .map(|&(start, end)| start.max(end))
+}
+
+/// Waveform of a take's audio, None if the browser can't decode it
+pub async fn decode_waveform(blob: &web_sys::Blob, bars: usize) -> Option<Vec<f32>> {
+    let buffer = JsFuture::from(blob.array_buffer()).await.ok()?;
+    let context = web_sys::AudioContext::new().ok()?;
+    let decoded = match context.decode_audio_data(buffer.unchecked_ref()) {
+        Ok(promise) => JsFuture::from(promise).await.ok(),
+        Err(_) => None,
+    };
+    let _ = context.close();
+    let samples = decoded?.dyn_into::<web_sys::AudioBuffer>().ok()?.get_channel_data(0).ok()?;
+    Some(waveform(&samples, bars))
+}
+
+/// The take cut down to its trim range with the cuts taken out, with its length
+/// and its markers where they landed. Each kept part starts at the keyframe
+/// before it.
+pub async fn trimmed(review: &Review) -> Result<(web_sys::Blob, f64, Vec<Marker>), RecorderError> {
+    if !review.is_edited() {
+        return Ok((review.blob.clone(), review.duration_ms, review.markers.clone()));
+    }
+    let ranges = review.kept_ranges();
+    if ranges.is_empty() {
+        return Err(RecorderError::RecordingFailed("Nothing of the recording is left to keep".into()));
+    }
+    let failed = |reason: String| RecorderError::RecordingFailed(format!("Could not trim the recording ({reason})"));
+
+    let buffer = JsFuture::from(review.blob.array_buffer())
+        .await
+        .map_err(|_| failed("unreadable".into()))?;
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    let (cut, kept) = webm::cut(&bytes, &ranges).map_err(|error| failed(error.to_string()))?;
+    let duration_ms = webm::Webm::parse(&cut).map_err(|error| failed(error.to_string()))?.duration_ms();
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(capability::container_type(&review.mime_type));
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(cut.as_slice()));
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|_| failed("out of memory".into()))?;
+    Ok((blob, duration_ms, markers::after_cuts(&review.markers, &kept)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waveform_scales_to_the_loudest_bar() {
+        let samples = [0.0, 0.1, -0.5, 0.25, 0.0, 0.0, 0.05, -0.1];
+        assert_eq!(waveform(&samples, 4), vec![0.2, 1.0, 0.0, 0.2]);
+    }
+
+    #[test]
+    fn waveform_with_more_bars_than_samples() {
+        let bars = waveform(&[0.5, -1.0], 4);
+        assert_eq!(bars.len(), 4);
+        assert!(bars.iter().all(|bar| (0.0..=1.0).contains(bar)));
+    }
+
+    #[test]
+    fn silent_or_empty_audio() {
+        assert_eq!(waveform(&[0.0; 10], 2), vec![0.0, 0.0]);
+        assert!(waveform(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn trim_stays_inside_the_take() {
+        assert_eq!(clamp_trim(-100.0, 20_000.0, 10_000.0), (0.0, 10_000.0));
+        assert_eq!(clamp_trim(9_900.0, 9_950.0, 10_000.0), (9_500.0, 10_000.0));
+        assert_eq!(clamp_trim(2_000.0, 2_100.0, 10_000.0), (2_000.0, 2_500.0));
+        assert_eq!(clamp_trim(0.0, 100.0, 300.0), (0.0, 300.0));
+    }
+
+    #[test]
+    fn cuts_come_out_of_the_kept_part() {
+        assert_eq!(kept_ranges((0.0, 10_000.0), &[]), vec![(0.0, 10_000.0)]);
+        assert_eq!(
+            kept_ranges((0.0, 10_000.0), &[(6_000.0, 7_000.0), (2_000.0, 3_000.0)]),
+            vec![(0.0, 2_000.0), (3_000.0, 6_000.0), (7_000.0, 10_000.0)]
+        );
+        // Overlapping, backwards and out-of-range cuts
+        assert_eq!(
+            kept_ranges((1_000.0, 9_000.0), &[(4_000.0, 2_000.0), (3_000.0, 5_000.0), (8_500.0, 12_000.0), (0.0, 500.0)]),
+            vec![(1_000.0, 2_000.0), (5_000.0, 8_500.0)]
+        );
+    }
+
+    #[test]
+    fn cutting_everything_leaves_nothing() {
+        assert!(kept_ranges((0.0, 5_000.0), &[(0.0, 2_000.0), (1_500.0, 5_000.0)]).is_empty());
+    }
+
+    #[test]
+    fn playback_skips_cuts() {
+        let cuts = [(2_000.0, 3_000.0), (6_000.0, 5_000.0)];
+        assert_eq!(skip_cut(&cuts, 2_500.0), Some(3_000.0));
+        assert_eq!(skip_cut(&cuts, 5_000.0), Some(6_000.0));
+        assert_eq!(skip_cut(&cuts, 3_000.0), None);
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::background::{LetterboxFill, VirtualBackground};
+use crate::error::RecorderError;
+use crate::frame::FrameStyle;
+use crate::layout::{CameraShape, Layout};
+use crate::redact::RedactionRegion;
+use crate::storage;
+
+const SCENES_KEY: &str = "demrec.scenes";
+
+/// How long fade and slide transitions between scenes take
+pub const SCENE_TRANSITION_MS: f64 = 600.0;
+
+/// How the compositor moves from the previous frame to a recalled scene
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SceneTransition {
+    Cut,
+    Fade,
+    /// The previous frame slides out to the left, uncovering the new one
+    Slide,
+}
+
+impl SceneTransition {
+    pub const ALL: [SceneTransition; 3] = [SceneTransition::Cut, SceneTransition::Fade, SceneTransition::Slide];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SceneTransition::Cut => "Cut",
+            SceneTransition::Fade => "Fade",
+            SceneTransition::Slide => "Slide",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|transition| transition.label() == label)
+    }
+
+    /// Draw the previous frame over the new one, `progress` going from 0 to 1
+    pub fn draw(
+        &self,
+        ctx: &web_sys::CanvasRenderingContext2d,
+        previous: &web_sys::HtmlCanvasElement,
+        width: f64,
+        height: f64,
+        progress: f64,
+    ) {
+        let progress = progress.clamp(0.0, 1.0);
+        if progress >= 1.0 {
+            return;
+        }
+
+        ctx.save();
+        match self {
+            SceneTransition::Cut => {}
+            SceneTransition::Fade => {
+                ctx.set_global_alpha(1.0 - progress);
+                let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(previous, 0.0, 0.0, width, height);
+            }
+            SceneTransition::Slide => {
+                let eased = progress * progress * (3.0 - 2.0 * progress);
+                let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
+                    previous,
+                    -width * eased,
+                    0.0,
+                    width,
+                    height,
+                );
+            }
+        }
+        ctx.restore();
+    }
+}
+
+/// A complete composition that can be recalled in one go
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    /// Key that recalls this scene (single character, case-insensitive)
+    pub key: String,
+    pub transition: SceneTransition,
+    pub layout: Layout,
+    /// Whether the camera is on; None in scenes saved before this was kept,
+    /// which leave the camera as it is
+    #[serde(default)]
+    pub camera_enabled: Option<bool>,
+    /// Whether the camera is hidden by its privacy style, None as above
+    #[serde(default)]
+    pub camera_private: Option<bool>,
+    #[serde(default)]
+    pub camera_shape: CameraShape,
+    /// Camera bubble position and size
+    pub camera_position: (f64, f64),
+    pub camera_size: (f64, f64),
+    pub camera_zoom: f64,
+    /// Screen crop in screen video coordinates
+    pub crop: Option<(f64, f64, f64, f64)>,
+    pub redactions: Vec<RedactionRegion>,
+    pub frame_style: FrameStyle,
+    pub letterbox_fill: LetterboxFill,
+    pub virtual_background: VirtualBackground,
+}
+
+impl Scene {
+    pub fn matches_key(&self, key: &str) -> bool {
+        !self.key.is_empty() && self.key.eq_ignore_ascii_case(key)
+    }
+}
+
+pub fn load_scenes() -> Vec<Scene> {
+    storage::load_json(SCENES_KEY).unwrap_or_default()
+}
+
+pub fn save_scenes(scenes: &[Scene]) -> Result<(), RecorderError> {
+    storage::try_save_json(SCENES_KEY, &scenes, "scenes")
+}
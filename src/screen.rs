@@ -0,0 +1,42 @@
+pub use crate::geometry::ScreenTransform;
+
+/// Where the screen pixels come from: the live share or a frozen copy of it
+#[derive(Clone)]
+pub enum ScreenSource {
+    Live(web_sys::HtmlVideoElement),
+    Frozen(web_sys::HtmlCanvasElement),
+}
+
+impl ScreenSource {
+    /// Draw a source rectangle of the screen into a destination rectangle
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        ctx: &web_sys::CanvasRenderingContext2d,
+        src_x: f64,
+        src_y: f64,
+        src_width: f64,
+        src_height: f64,
+        dest_x: f64,
+        dest_y: f64,
+        dest_width: f64,
+        dest_height: f64,
+    ) {
+        let _ = match self {
+            ScreenSource::Live(video) => ctx.draw_image_with_html_video_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                video, src_x, src_y, src_width, src_height, dest_x, dest_y, dest_width, dest_height,
+            ),
+            ScreenSource::Frozen(canvas) => ctx.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                canvas, src_x, src_y, src_width, src_height, dest_x, dest_y, dest_width, dest_height,
+            ),
+        };
+    }
+
+    /// Size of the source in screen video pixels
+    pub fn size(&self) -> (f64, f64) {
+        match self {
+            ScreenSource::Live(video) => (video.video_width() as f64, video.video_height() as f64),
+            ScreenSource::Frozen(canvas) => (canvas.width() as f64, canvas.height() as f64),
+        }
+    }
+}
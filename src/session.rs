@@ -0,0 +1,238 @@
+//! Recording sessions kept on disk as they are recorded, so a crash or a closed
+//! tab doesn't lose the take. Each chunk from the MediaRecorder goes to its own
+//! file in the origin private file system under `sessions/<id>/`, and each
+//! session that hasn't been saved yet has its own entry in localStorage, so
+//! tabs recording at the same time don't overwrite each other's.
+//!
+//! The tab recording a session refreshes it every few seconds until it is
+//! saved or discarded; only sessions that have gone quiet for a while are
+//! taken to be abandoned, so another tab never recovers or deletes a live one.
+
+use serde::{Deserialize, Serialize};
+
+use crate::capability;
+use crate::error::RecorderError;
+use crate::markers::Marker;
+use crate::{library, opfs, storage};
+
+const SESSION_KEY_PREFIX: &str = "demrec.session.";
+// All sessions in one list, as kept before each had its own entry
+const LEGACY_SESSIONS_KEY: &str = "demrec.sessions";
+const SESSIONS_DIR: &str = "sessions";
+
+/// Milliseconds of recording per chunk written to disk
+pub const CHUNK_MS: i32 = 1000;
+
+/// How often the recording tab marks its session as live
+pub const HEARTBEAT_MS: u32 = 5_000;
+
+// Quiet for this long and a session is taken to be abandoned; well above the
+// heartbeat, since browsers slow timers in background tabs to once a minute
+const STALE_MS: f64 = 3.0 * 60_000.0;
+
+/// A recording that is under way, or was cut short and can be recovered
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSession {
+    pub id: String,
+    pub mime_type: String,
+    /// Milliseconds since the epoch
+    pub started_at: f64,
+    /// Chunks are numbered from 0; some below this may be missing if a write failed
+    pub chunk_count: u32,
+    /// Bytes written so far
+    pub size: f64,
+    /// Dropped so far, recovered with the recording
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+    /// When the recording tab last wrote to the session, milliseconds since the epoch
+    #[serde(default)]
+    pub updated_at: f64,
+}
+
+impl RecordingSession {
+    pub fn new(mime_type: &str, started_at: f64) -> Self {
+        Self {
+            id: format!("{}", started_at as u64),
+            mime_type: mime_type.to_string(),
+            started_at,
+            chunk_count: 0,
+            size: 0.0,
+            markers: Vec::new(),
+            updated_at: started_at,
+        }
+    }
+
+    /// Whether the tab recording it has gone, going by when it last wrote
+    pub fn is_abandoned(&self, now: f64) -> bool {
+        now - self.updated_at.max(self.started_at) > STALE_MS
+    }
+}
+
+fn session_key(id: &str) -> String {
+    format!("{SESSION_KEY_PREFIX}{id}")
+}
+
+fn chunk_name(index: u32) -> String {
+    format!("chunk-{index:05}")
+}
+
+// Note a chunk as written
+fn record_chunk(session: &mut RecordingSession, index: u32, size: f64) {
+    session.chunk_count = session.chunk_count.max(index + 1);
+    session.size += size;
+}
+
+/// Sessions that were started but never saved or discarded, oldest first
+pub fn load_sessions() -> Vec<RecordingSession> {
+    // Give sessions from the old shared list their own entries
+    if let Some(legacy) = storage::load_json::<Vec<RecordingSession>>(LEGACY_SESSIONS_KEY) {
+        if legacy.iter().all(|session| save_session(session).is_ok()) {
+            storage::remove(LEGACY_SESSIONS_KEY);
+        }
+    }
+    let mut sessions: Vec<RecordingSession> = storage::keys_with_prefix(SESSION_KEY_PREFIX)
+        .iter()
+        .filter_map(|key| storage::load_json(key))
+        .collect();
+    sessions.sort_by(|a, b| a.started_at.total_cmp(&b.started_at));
+    sessions
+}
+
+/// Sessions no tab is recording any more, to recover or discard
+pub fn load_abandoned() -> Vec<RecordingSession> {
+    let now = js_sys::Date::now();
+    load_sessions().into_iter().filter(|session| session.is_abandoned(now)).collect()
+}
+
+fn save_session(session: &RecordingSession) -> Result<(), RecorderError> {
+    storage::try_save_json(&session_key(&session.id), session, "the recording session")
+}
+
+// Change a session this tab is recording and mark it as live; gone already is fine
+fn update_session(id: &str, change: impl FnOnce(&mut RecordingSession)) -> Result<(), RecorderError> {
+    let Some(mut session) = storage::load_json::<RecordingSession>(&session_key(id)) else {
+        return Ok(());
+    };
+    change(&mut session);
+    session.updated_at = js_sys::Date::now();
+    save_session(&session)
+}
+
+/// Start keeping a new recording on disk
+pub async fn begin(mime_type: &str) -> Result<RecordingSession, RecorderError> {
+    let session = RecordingSession::new(mime_type, js_sys::Date::now());
+    opfs::path(&[SESSIONS_DIR, &session.id], true)
+        .await
+        .map_err(|error| opfs::storage_error("the recording session", &error))?;
+    save_session(&session)?;
+    Ok(session)
+}
+
+/// Write one chunk of the recording to disk
+pub async fn save_chunk(id: &str, index: u32, chunk: &web_sys::Blob) -> Result<(), RecorderError> {
+    let write = async {
+        let dir = opfs::path(&[SESSIONS_DIR, id], false).await?;
+        opfs::write_file(&dir, &chunk_name(index), chunk).await
+    };
+    write
+        .await
+        .map_err(|error| opfs::storage_error("the recording in progress", &error))?;
+    update_session(id, |session| record_chunk(session, index, chunk.size()))
+}
+
+/// Note the markers dropped so far, so they survive a crash with the recording
+pub fn save_markers(id: &str, markers: &[Marker]) -> Result<(), RecorderError> {
+    update_session(id, |session| session.markers = markers.to_vec())
+}
+
+/// Mark a session as still live, while it is paused or being reviewed and no chunks come in
+pub fn touch(id: &str) -> Result<(), RecorderError> {
+    update_session(id, |_| {})
+}
+
+/// Forget a session and delete its chunks, once it is saved or not wanted
+pub async fn discard(id: &str) -> Result<(), RecorderError> {
+    storage::remove(&session_key(id));
+
+    // Gone already is fine
+    if let Ok(dir) = opfs::path(&[SESSIONS_DIR], false).await {
+        let _ = opfs::remove(&dir, id).await;
+    }
+    Ok(())
+}
+
+/// Put a cut-short recording back together into a playable, seekable file,
+/// returning it with its length in milliseconds, estimated from the chunks
+/// read where the file doesn't say
+pub async fn recover(session: &RecordingSession) -> Result<(web_sys::Blob, f64), RecorderError> {
+    let dir = opfs::path(&[SESSIONS_DIR, &session.id], false)
+        .await
+        .map_err(|error| opfs::storage_error("the unfinished recording", &error))?;
+
+    // Chunks are cut mid-cluster, so nothing after a missing one can be read;
+    // the recording ends where the first write failed
+    let parts = js_sys::Array::new();
+    for index in 0..session.chunk_count {
+        let Ok(file) = opfs::read_file(&dir, &chunk_name(index)).await else {
+            break;
+        };
+        parts.push(&file);
+    }
+    if parts.length() == 0 {
+        return Err(RecorderError::RecordingFailed("Nothing of the recording was saved".into()));
+    }
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(capability::container_type(&session.mime_type));
+    let blob = web_sys::Blob::new_with_blob_sequence_and_options(&parts, &options)
+        .map_err(|_| RecorderError::RecordingFailed("Could not assemble the recording".into()))?;
+    let (blob, measured) = library::make_seekable(blob, &session.mime_type).await;
+    Ok((blob, measured.unwrap_or((parts.length() as i32 * CHUNK_MS) as f64)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> RecordingSession {
+        RecordingSession {
+            id: id.to_string(),
+            mime_type: "video/webm".to_string(),
+            started_at: 0.0,
+            chunk_count: 0,
+            size: 0.0,
+            markers: Vec::new(),
+            updated_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn chunks_sort_in_recording_order() {
+        let mut names: Vec<_> = [10, 2, 100, 1].into_iter().map(chunk_name).collect();
+        names.sort();
+        assert_eq!(names, ["chunk-00001", "chunk-00002", "chunk-00010", "chunk-00100"]);
+    }
+
+    #[test]
+    fn chunks_written_out_of_order_count_up_to_the_last() {
+        let mut recorded = session("a");
+        record_chunk(&mut recorded, 1, 100.0);
+        record_chunk(&mut recorded, 0, 50.0);
+        assert_eq!(recorded.chunk_count, 2);
+        assert_eq!(recorded.size, 150.0);
+    }
+
+    #[test]
+    fn only_quiet_sessions_are_abandoned() {
+        let mut live = RecordingSession::new("video/webm", 1_000.0);
+        assert!(!live.is_abandoned(1_000.0 + STALE_MS));
+        assert!(live.is_abandoned(1_001.0 + STALE_MS));
+        live.updated_at = 60_000.0;
+        assert!(!live.is_abandoned(60_000.0 + STALE_MS));
+
+        // Kept before the heartbeat, so only the start says how old it is
+        let legacy = RecordingSession { started_at: 1_000.0, ..session("old") };
+        assert!(!legacy.is_abandoned(2_000.0));
+        assert!(legacy.is_abandoned(1_001.0 + STALE_MS));
+    }
+}
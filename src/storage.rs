@@ -0,0 +1,86 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::RecorderError;
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Read a JSON value from localStorage, None if missing or unparseable
+pub fn load_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let json = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+/// Write a JSON value to localStorage, returning false if it could not be stored
+pub fn save_json<T: Serialize>(key: &str, value: &T) -> bool {
+    let (Some(storage), Ok(json)) = (local_storage(), serde_json::to_string(value)) else {
+        return false;
+    };
+    storage.set_item(key, &json).is_ok()
+}
+
+/// Keys in localStorage starting with `prefix`
+pub fn keys_with_prefix(prefix: &str) -> Vec<String> {
+    let Some(storage) = local_storage() else {
+        return Vec::new();
+    };
+    let count = storage.length().unwrap_or(0);
+    (0..count)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter(|key| key.starts_with(prefix))
+        .collect()
+}
+
+/// Remove a value from localStorage, if there
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+/// Write a JSON value to localStorage, failing with an error naming `what`:
+/// storage-full when over the quota, otherwise the cause
+pub fn try_save_json<T: Serialize>(key: &str, value: &T, what: &str) -> Result<(), RecorderError> {
+    let storage = local_storage()
+        .ok_or_else(|| RecorderError::StorageFailed(format!("Could not save {what}, localStorage is unavailable")))?;
+    let json = serde_json::to_string(value)
+        .map_err(|error| RecorderError::StorageFailed(format!("Could not save {what} ({error})")))?;
+    storage.set_item(key, &json).map_err(|error| {
+        let field = |name: &str| {
+            js_sys::Reflect::get(&error, &name.into())
+                .ok()
+                .and_then(|value| value.as_string())
+                .unwrap_or_default()
+        };
+        save_error(what, &field("name"), &field("message"))
+    })
+}
+
+// Error for a failed setItem from the DOMException name and message
+fn save_error(what: &str, name: &str, message: &str) -> RecorderError {
+    match name {
+        // Firefox used its own name for the quota before adopting the standard one
+        "QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED" => RecorderError::StorageFull(what.to_string()),
+        _ if message.is_empty() => RecorderError::StorageFailed(format!("Could not save {what} ({name})")),
+        _ => RecorderError::StorageFailed(format!("Could not save {what} ({name}: {message})")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_quota_is_storage_full() {
+        assert_eq!(
+            save_error("scenes", "QuotaExceededError", "The quota has been exceeded."),
+            RecorderError::StorageFull("scenes".to_string())
+        );
+        assert_eq!(
+            save_error("scenes", "SecurityError", "The operation is insecure."),
+            RecorderError::StorageFailed("Could not save scenes (SecurityError: The operation is insecure.)".to_string())
+        );
+    }
+}
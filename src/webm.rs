@@ -0,0 +1,1305 @@
+//! Just enough WebM (Matroska/EBML) parsing to repair and edit MediaRecorder
+//! output without re-encoding. Pure Rust, so it is tested natively.
+
+use std::fmt;
+
+/// Element ids used here, with their length marker bits included as in the file
+pub mod id {
+    pub const EBML: u32 = 0x1A45_DFA3;
+    pub const SEGMENT: u32 = 0x1853_8067;
+    pub const SEEK_HEAD: u32 = 0x114D_9B74;
+    pub const SEEK: u32 = 0x4DBB;
+    pub const SEEK_ID: u32 = 0x53AB;
+    pub const SEEK_POSITION: u32 = 0x53AC;
+    pub const INFO: u32 = 0x1549_A966;
+    pub const TIMECODE_SCALE: u32 = 0x2A_D7B1;
+    pub const DURATION: u32 = 0x4489;
+    pub const TRACKS: u32 = 0x1654_AE6B;
+    pub const TRACK_ENTRY: u32 = 0xAE;
+    pub const TRACK_NUMBER: u32 = 0xD7;
+    pub const TRACK_TYPE: u32 = 0x83;
+    pub const CODEC_ID: u32 = 0x86;
+    pub const CODEC_PRIVATE: u32 = 0x63A2;
+    pub const VIDEO: u32 = 0xE0;
+    pub const PIXEL_WIDTH: u32 = 0xB0;
+    pub const PIXEL_HEIGHT: u32 = 0xBA;
+    pub const AUDIO: u32 = 0xE1;
+    pub const SAMPLING_FREQUENCY: u32 = 0xB5;
+    pub const CHANNELS: u32 = 0x9F;
+    pub const CLUSTER: u32 = 0x1F43_B675;
+    pub const CUES: u32 = 0x1C53_BB6B;
+    pub const CHAPTERS: u32 = 0x1043_A770;
+    pub const EDITION_ENTRY: u32 = 0x45B9;
+    pub const CHAPTER_ATOM: u32 = 0xB6;
+    pub const CHAPTER_UID: u32 = 0x73C4;
+    pub const CHAPTER_TIME_START: u32 = 0x91;
+    pub const CHAPTER_TIME_END: u32 = 0x92;
+    pub const CHAPTER_DISPLAY: u32 = 0x80;
+    pub const CHAP_STRING: u32 = 0x85;
+    pub const CHAP_LANGUAGE: u32 = 0x437C;
+    pub const CUE_POINT: u32 = 0xBB;
+    pub const CUE_TIME: u32 = 0xB3;
+    pub const CUE_TRACK_POSITIONS: u32 = 0xB7;
+    pub const CUE_TRACK: u32 = 0xF7;
+    pub const CUE_CLUSTER_POSITION: u32 = 0xF1;
+    pub const TIMECODE: u32 = 0xE7;
+    pub const POSITION: u32 = 0xA7;
+    pub const PREV_SIZE: u32 = 0xAB;
+    pub const SIMPLE_BLOCK: u32 = 0xA3;
+    pub const BLOCK_GROUP: u32 = 0xA0;
+    pub const BLOCK: u32 = 0xA1;
+    pub const REFERENCE_BLOCK: u32 = 0xFB;
+    pub const VOID: u32 = 0xEC;
+    pub const CRC32: u32 = 0xBF;
+}
+
+/// Default length of a timecode unit, in nanoseconds (1 ms)
+pub const DEFAULT_TIMECODE_SCALE: u64 = 1_000_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WebmError {
+    /// Doesn't start with an EBML header and a segment
+    NotWebm,
+    /// Ends before a required element is complete
+    Truncated,
+    /// Valid, but laid out in a way this module can't edit
+    Unsupported(&'static str),
+    /// A file to be joined, by index, is encoded differently from the first, saying how
+    Mismatch { file: usize, difference: String },
+}
+
+impl fmt::Display for WebmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebmError::NotWebm => write!(f, "not a WebM file"),
+            WebmError::Truncated => write!(f, "the WebM file is cut off"),
+            WebmError::Unsupported(what) => write!(f, "unsupported WebM layout: {what}"),
+            WebmError::Mismatch { file, difference } => write!(f, "recording {} {difference}", file + 1),
+        }
+    }
+}
+
+impl std::error::Error for WebmError {}
+
+// Read a variable-length integer: (value without the length marker, length in
+// bytes, whether all value bits are set, which marks an unknown size)
+fn read_vint(data: &[u8], pos: usize) -> Option<(u64, usize, bool)> {
+    let first = *data.get(pos)?;
+    let length = first.leading_zeros() as usize + 1;
+    if length > 8 {
+        return None;
+    }
+    let bytes = data.get(pos..pos + length)?;
+
+    let mut value = (first as u64) & (0xFF >> length);
+    for &byte in &bytes[1..] {
+        value = (value << 8) | byte as u64;
+    }
+    let all_ones = value == (1u64 << (7 * length)) - 1;
+    Some((value, length, all_ones))
+}
+
+// Read an element id, keeping its marker bits
+fn read_id(data: &[u8], pos: usize) -> Option<(u32, usize)> {
+    let first = *data.get(pos)?;
+    let length = first.leading_zeros() as usize + 1;
+    if length > 4 {
+        return None;
+    }
+    let bytes = data.get(pos..pos + length)?;
+    Some((bytes.iter().fold(0u32, |id, &byte| (id << 8) | byte as u32), length))
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().take(8).fold(0u64, |value, &byte| (value << 8) | byte as u64)
+}
+
+fn read_float(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        4 => Some(f32::from_be_bytes(bytes.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+/// Encode an element id
+pub fn encode_id(id: u32) -> Vec<u8> {
+    let length = (4 - id.leading_zeros() as usize / 8).max(1);
+    id.to_be_bytes()[4 - length..].to_vec()
+}
+
+/// Encode an element size in 8 bytes, so it can be patched later without moving data
+pub fn encode_size(size: u64) -> Vec<u8> {
+    let mut bytes = size.to_be_bytes();
+    bytes[0] = 0x01;
+    bytes.to_vec()
+}
+
+/// An unsigned integer element, written at full width
+pub fn encode_uint(id: u32, value: u64) -> Vec<u8> {
+    encode_element(id, &value.to_be_bytes())
+}
+
+/// A whole element: id, size and body
+pub fn encode_element(id: u32, body: &[u8]) -> Vec<u8> {
+    let mut bytes = encode_id(id);
+    bytes.extend(encode_size(body.len() as u64));
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+/// Where an element sits in the file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Element {
+    pub id: u32,
+    /// Offset of the id
+    pub start: usize,
+    /// Offset of the body
+    pub data_start: usize,
+    /// Offset just past the body, for cut-off elements the end of the last complete child
+    pub end: usize,
+}
+
+impl Element {
+    pub fn bytes<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        &data[self.start..self.end]
+    }
+
+    pub fn body<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        &data[self.data_start..self.end]
+    }
+}
+
+fn is_cluster_child(id: u32) -> bool {
+    matches!(
+        id,
+        id::TIMECODE | id::POSITION | id::PREV_SIZE | id::SIMPLE_BLOCK | id::BLOCK_GROUP | id::VOID | id::CRC32
+    )
+}
+
+// Read the element at `pos`, which must end by `limit`. Clusters of unknown
+// size (as Chrome writes them) or cut off by a crash end after their last
+// complete child. None if not even the header is there.
+fn read_element(data: &[u8], pos: usize, limit: usize) -> Option<Element> {
+    let (id, id_length) = read_id(data, pos)?;
+    let (size, size_length, unknown) = read_vint(data, pos + id_length)?;
+    let data_start = pos + id_length + size_length;
+    if data_start > limit {
+        return None;
+    }
+
+    let declared_end = (!unknown).then(|| data_start.saturating_add(size as usize));
+    let end = match declared_end {
+        Some(end) if end <= limit => end,
+        _ if id == id::CLUSTER => {
+            let mut end = data_start;
+            while let Some(child) = read_element(data, end, limit) {
+                if !is_cluster_child(child.id) {
+                    break;
+                }
+                end = child.end;
+            }
+            end
+        }
+        // The segment runs to the end of the file
+        _ if id == id::SEGMENT => limit,
+        _ => return None,
+    };
+
+    Some(Element {
+        id,
+        start: pos,
+        data_start,
+        end,
+    })
+}
+
+/// Complete children of an element
+pub fn children(data: &[u8], parent: Element) -> Vec<Element> {
+    let mut elements = Vec::new();
+    let mut pos = parent.data_start;
+    while let Some(element) = read_element(data, pos, parent.end) {
+        if element.end == pos {
+            break;
+        }
+        pos = element.end;
+        elements.push(element);
+    }
+    elements
+}
+
+/// A frame of one track
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// The SimpleBlock or BlockGroup holding it
+    pub element: Element,
+    /// Offset of the cluster it is in
+    pub cluster: usize,
+    pub track: u64,
+    /// Absolute time in timecode-scale units
+    pub timecode: i64,
+    pub keyframe: bool,
+}
+
+// Track, relative timecode and keyframe flag from a SimpleBlock or Block body
+fn read_block_header(body: &[u8]) -> Option<(u64, i16, u8)> {
+    let (track, length, _) = read_vint(body, 0)?;
+    let timecode = i16::from_be_bytes(body.get(length..length + 2)?.try_into().ok()?);
+    let flags = *body.get(length + 2)?;
+    Some((track, timecode, flags))
+}
+
+/// What a track carries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Other,
+}
+
+/// A track of the file, from its entry in Tracks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Track {
+    pub number: u64,
+    pub kind: TrackKind,
+    /// Matroska codec id, such as "V_VP9" or "A_OPUS"
+    pub codec: String,
+    /// Codec setup the decoder needs before the first frame
+    pub codec_private: Vec<u8>,
+    /// Video frame size in pixels
+    pub size: Option<(u64, u64)>,
+    /// Audio sample rate in Hz
+    pub sample_rate: Option<u64>,
+    pub channels: Option<u64>,
+}
+
+impl Track {
+    /// Codec and kind as people say it, such as "VP9 video"
+    pub fn description(&self) -> String {
+        let codec = self.codec.split_once('_').map_or(self.codec.as_str(), |(_, name)| name);
+        let codec = match codec {
+            "OPUS" => "Opus",
+            "VORBIS" => "Vorbis",
+            "MPEG4/ISO/AVC" => "H.264",
+            other => other,
+        };
+        match self.kind {
+            TrackKind::Video => format!("{codec} video"),
+            TrackKind::Audio => format!("{codec} audio"),
+            TrackKind::Other => codec.to_string(),
+        }
+    }
+}
+
+// Block body with its timecode relative to the cluster replaced
+fn with_block_timecode(body: &[u8], relative: i16) -> Vec<u8> {
+    let mut body = body.to_vec();
+    if let Some((_, length, _)) = read_vint(&body, 0) {
+        if let Some(timecode) = body.get_mut(length..length + 2) {
+            timecode.copy_from_slice(&relative.to_be_bytes());
+        }
+    }
+    body
+}
+
+// A cluster being assembled by `clusters_for`
+struct ClusterBuilder {
+    /// File and cluster offset its blocks come from
+    source: (usize, usize),
+    timecode: i64,
+    body: Vec<u8>,
+    /// Decided at the first frame of the cue track: whether it is a keyframe, and its time
+    cue: Option<Option<i64>>,
+}
+
+// Seek head pointing at elements by their offset from the start of the segment body
+fn encode_seek_head(entries: &[(u32, usize)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for &(element_id, position) in entries {
+        let mut seek = encode_element(id::SEEK_ID, &encode_id(element_id));
+        seek.extend(encode_uint(id::SEEK_POSITION, position as u64));
+        body.extend(encode_element(id::SEEK, &seek));
+    }
+    encode_element(id::SEEK_HEAD, &body)
+}
+
+// Cues for keyframes of `track` at the start of clusters, by time and cluster offset
+fn encode_cues(points: &[(i64, usize)], track: u64) -> Vec<u8> {
+    let mut body = Vec::new();
+    for &(time, position) in points {
+        let mut positions = encode_uint(id::CUE_TRACK, track);
+        positions.extend(encode_uint(id::CUE_CLUSTER_POSITION, position as u64));
+        let mut point = encode_uint(id::CUE_TIME, time as u64);
+        point.extend(encode_element(id::CUE_TRACK_POSITIONS, &positions));
+        body.extend(encode_element(id::CUE_POINT, &point));
+    }
+    encode_element(id::CUES, &body)
+}
+
+// A block on the new timeline: index of the file it comes from, the block, and its new time
+type Placed = (usize, Block, i64);
+
+/// Where a stretch of a source file ended up in a cut or joined file, in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeptPart {
+    /// Index of the file it comes from
+    pub source: usize,
+    /// Source time of its first frame
+    pub source_start_ms: f64,
+    /// Source time where its last frame ends
+    pub source_end_ms: f64,
+    /// Time of its first frame in the new file
+    pub start_ms: f64,
+}
+
+impl KeptPart {
+    /// Where a source time inside this part lands in the new file
+    pub fn place(&self, source_ms: f64) -> f64 {
+        self.start_ms + source_ms - self.source_start_ms
+    }
+}
+
+// Clusters holding `blocks` at their new timecodes, in order, each with the
+// time of its first frame of `cue_track` if that is a keyframe. Blocks stay
+// grouped as in their source, with a new cluster wherever the relative
+// timecode would overflow.
+fn clusters_for(sources: &[&Webm], blocks: &[Placed], cue_track: u64) -> Vec<(Vec<u8>, Option<i64>)> {
+    let mut clusters: Vec<ClusterBuilder> = Vec::new();
+    for (source, block, timecode) in blocks {
+        let same_cluster = clusters.last().is_some_and(|cluster| {
+            cluster.source == (*source, block.cluster) && (0..=i16::MAX as i64).contains(&(timecode - cluster.timecode))
+        });
+        if !same_cluster {
+            clusters.push(ClusterBuilder {
+                source: (*source, block.cluster),
+                timecode: *timecode,
+                body: encode_uint(id::TIMECODE, *timecode as u64),
+                cue: None,
+            });
+        }
+        if let Some(cluster) = clusters.last_mut() {
+            if block.track == cue_track && cluster.cue.is_none() {
+                cluster.cue = Some(block.keyframe.then_some(*timecode));
+            }
+            cluster.body.extend(sources[*source].retimed_block(block, (timecode - cluster.timecode) as i16));
+        }
+    }
+    clusters
+        .into_iter()
+        .map(|cluster| (encode_element(id::CLUSTER, &cluster.body), cluster.cue.flatten()))
+        .collect()
+}
+
+// Chapters starting at the given milliseconds, each running to the next or to `end_ms`
+fn encode_chapters(chapters: &[(f64, String)], end_ms: f64) -> Vec<u8> {
+    let nanoseconds = |ms: f64| (ms.max(0.0) * 1_000_000.0).round() as u64;
+    let mut edition = Vec::new();
+    for (index, (start_ms, title)) in chapters.iter().enumerate() {
+        let next_ms = chapters.get(index + 1).map_or(end_ms, |(next, _)| *next).max(*start_ms);
+        let mut display = encode_element(id::CHAP_STRING, title.as_bytes());
+        display.extend(encode_element(id::CHAP_LANGUAGE, b"eng"));
+        let mut atom = encode_uint(id::CHAPTER_UID, index as u64 + 1);
+        atom.extend(encode_uint(id::CHAPTER_TIME_START, nanoseconds(*start_ms)));
+        atom.extend(encode_uint(id::CHAPTER_TIME_END, nanoseconds(next_ms)));
+        atom.extend(encode_element(id::CHAPTER_DISPLAY, &display));
+        edition.extend(encode_element(id::CHAPTER_ATOM, &atom));
+    }
+    encode_element(id::CHAPTERS, &encode_element(id::EDITION_ENTRY, &edition))
+}
+
+/// A parsed WebM file
+#[derive(Clone, Debug)]
+pub struct Webm<'a> {
+    data: &'a [u8],
+    pub header: Element,
+    pub segment: Element,
+    /// Complete top-level elements of the segment: info, tracks, clusters, ...
+    pub elements: Vec<Element>,
+}
+
+impl<'a> Webm<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, WebmError> {
+        let header = read_element(data, 0, data.len()).ok_or(WebmError::NotWebm)?;
+        if header.id != id::EBML {
+            return Err(WebmError::NotWebm);
+        }
+        let segment = read_element(data, header.end, data.len()).ok_or(WebmError::NotWebm)?;
+        if segment.id != id::SEGMENT {
+            return Err(WebmError::NotWebm);
+        }
+        let elements = children(data, segment);
+        if !elements.iter().any(|element| element.id == id::INFO) {
+            return Err(WebmError::Truncated);
+        }
+
+        Ok(Self {
+            data,
+            header,
+            segment,
+            elements,
+        })
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn find(&self, id: u32) -> Option<Element> {
+        self.elements.iter().copied().find(|element| element.id == id)
+    }
+
+    pub fn clusters(&self) -> impl Iterator<Item = Element> + '_ {
+        self.elements.iter().copied().filter(|element| element.id == id::CLUSTER)
+    }
+
+    /// Nanoseconds per timecode unit
+    pub fn timecode_scale(&self) -> u64 {
+        self.find(id::INFO)
+            .and_then(|info| {
+                children(self.data, info)
+                    .into_iter()
+                    .find(|element| element.id == id::TIMECODE_SCALE)
+            })
+            .map(|scale| read_uint(scale.body(self.data)))
+            .filter(|&scale| scale > 0)
+            .unwrap_or(DEFAULT_TIMECODE_SCALE)
+    }
+
+    /// Timecode of a cluster, in timecode-scale units
+    pub fn cluster_timecode(&self, cluster: Element) -> u64 {
+        children(self.data, cluster)
+            .into_iter()
+            .find(|element| element.id == id::TIMECODE)
+            .map(|timecode| read_uint(timecode.body(self.data)))
+            .unwrap_or(0)
+    }
+
+    /// Frames of a cluster, in file order
+    pub fn blocks(&self, cluster: Element) -> Vec<Block> {
+        let cluster_timecode = self.cluster_timecode(cluster) as i64;
+        children(self.data, cluster)
+            .into_iter()
+            .filter_map(|element| {
+                let (body, keyframe_flag) = match element.id {
+                    id::SIMPLE_BLOCK => (element.body(self.data), None),
+                    id::BLOCK_GROUP => {
+                        let parts = children(self.data, element);
+                        let block = parts.iter().find(|part| part.id == id::BLOCK)?;
+                        // A block without references to others is a keyframe
+                        let referenced = parts.iter().any(|part| part.id == id::REFERENCE_BLOCK);
+                        (block.body(self.data), Some(!referenced))
+                    }
+                    _ => return None,
+                };
+                let (track, timecode, flags) = read_block_header(body)?;
+                Some(Block {
+                    element,
+                    cluster: cluster.start,
+                    track,
+                    timecode: cluster_timecode + timecode as i64,
+                    keyframe: keyframe_flag.unwrap_or(flags & 0x80 != 0),
+                })
+            })
+            .collect()
+    }
+
+    pub fn tracks(&self) -> Vec<Track> {
+        let Some(tracks) = self.find(id::TRACKS) else {
+            return Vec::new();
+        };
+        children(self.data, tracks)
+            .into_iter()
+            .filter(|entry| entry.id == id::TRACK_ENTRY)
+            .map(|entry| {
+                let fields = children(self.data, entry);
+                let find = |fields: &[Element], field_id| fields.iter().find(|field| field.id == field_id).copied();
+                let field = |field_id| find(&fields, field_id).map(|field| field.body(self.data));
+                let video = find(&fields, id::VIDEO).map(|video| children(self.data, video)).unwrap_or_default();
+                let audio = find(&fields, id::AUDIO).map(|audio| children(self.data, audio)).unwrap_or_default();
+                let setting = |settings: &[Element], field_id| find(settings, field_id).map(|field| field.body(self.data));
+                Track {
+                    number: field(id::TRACK_NUMBER).map(read_uint).unwrap_or(0),
+                    kind: match field(id::TRACK_TYPE).map(read_uint) {
+                        Some(1) => TrackKind::Video,
+                        Some(2) => TrackKind::Audio,
+                        _ => TrackKind::Other,
+                    },
+                    codec: field(id::CODEC_ID)
+                        .map(|codec| String::from_utf8_lossy(codec).trim_end_matches('\0').to_string())
+                        .unwrap_or_default(),
+                    codec_private: field(id::CODEC_PRIVATE).unwrap_or_default().to_vec(),
+                    size: setting(&video, id::PIXEL_WIDTH)
+                        .zip(setting(&video, id::PIXEL_HEIGHT))
+                        .map(|(width, height)| (read_uint(width), read_uint(height))),
+                    sample_rate: setting(&audio, id::SAMPLING_FREQUENCY)
+                        .and_then(read_float)
+                        .map(|rate| rate.round() as u64),
+                    channels: setting(&audio, id::CHANNELS).map(read_uint),
+                }
+            })
+            .collect()
+    }
+
+    /// Milliseconds in timecode-scale units
+    pub fn to_timecode(&self, ms: f64) -> f64 {
+        ms * 1_000_000.0 / self.timecode_scale() as f64
+    }
+
+    /// Timecode-scale units in milliseconds
+    pub fn to_ms(&self, timecode: i64) -> f64 {
+        timecode as f64 * self.timecode_scale() as f64 / 1_000_000.0
+    }
+
+    /// A block written out at a new time relative to its cluster
+    fn retimed_block(&self, block: &Block, relative: i16) -> Vec<u8> {
+        if block.element.id == id::SIMPLE_BLOCK {
+            return encode_element(id::SIMPLE_BLOCK, &with_block_timecode(block.element.body(self.data), relative));
+        }
+        let mut body = Vec::new();
+        for part in children(self.data, block.element) {
+            if part.id == id::BLOCK {
+                body.extend(encode_element(id::BLOCK, &with_block_timecode(part.body(self.data), relative)));
+            } else {
+                body.extend_from_slice(part.bytes(self.data));
+            }
+        }
+        encode_element(id::BLOCK_GROUP, &body)
+    }
+
+    /// The file with its clusters replaced by `blocks` at new timecodes, the
+    /// duration set to match, and a new seek head and cues for seeking. Blocks
+    /// come from `sources` by index; this file gives the header and tracks.
+    /// New `chapters` (start in milliseconds, title) replace any the file has.
+    fn remux(&self, sources: &[&Webm], blocks: &[Placed], chapters: Option<&[(f64, String)]>) -> Result<Vec<u8>, WebmError> {
+        let cue_track = self.cue_track(blocks.iter().map(|(_, block, _)| block));
+        // Up to the end of the last frame, which lasts as long as the gap between frames
+        let cue_times = blocks.iter().filter(|(_, block, _)| block.track == cue_track).map(|(_, _, timecode)| *timecode);
+        let duration = match blocks.iter().map(|(_, _, timecode)| *timecode).max() {
+            Some(last) => last + frame_step(cue_times),
+            None => 0,
+        };
+        let mut clusters = Some(clusters_for(sources, blocks, cue_track));
+
+        // Everything after the seek head, noting where the elements it points to land
+        let mut rest = Vec::new();
+        let mut seek_entries = Vec::new();
+        let mut cue_points = Vec::new();
+        for element in &self.elements {
+            match element.id {
+                id::INFO => {
+                    seek_entries.push((id::INFO, rest.len()));
+                    rest.extend(self.info_with_duration(duration as f64)?);
+                }
+                id::CLUSTER => {
+                    for (cluster, cue) in clusters.take().into_iter().flatten() {
+                        if let Some(time) = cue {
+                            cue_points.push((time, rest.len()));
+                        }
+                        rest.extend(cluster);
+                    }
+                }
+                id::SEEK_HEAD | id::CUES | id::VOID => {}
+                id::CHAPTERS if chapters.is_some() => {}
+                id::TRACKS => {
+                    seek_entries.push((id::TRACKS, rest.len()));
+                    rest.extend_from_slice(element.bytes(self.data));
+                    if let Some(chapters) = chapters.filter(|chapters| !chapters.is_empty()) {
+                        seek_entries.push((id::CHAPTERS, rest.len()));
+                        let end_ms = duration as f64 * self.timecode_scale() as f64 / 1_000_000.0;
+                        rest.extend(encode_chapters(chapters, end_ms));
+                    }
+                }
+                element_id => {
+                    if element_id == id::CHAPTERS {
+                        seek_entries.push((id::CHAPTERS, rest.len()));
+                    }
+                    rest.extend_from_slice(element.bytes(self.data));
+                }
+            }
+        }
+        if !cue_points.is_empty() {
+            seek_entries.push((id::CUES, rest.len()));
+        }
+
+        // Numbers in the seek head are written at full width, so its size doesn't depend on the offsets
+        let head_size = encode_seek_head(&seek_entries).len();
+        let seek_entries: Vec<_> = seek_entries.into_iter().map(|(key, offset)| (key, offset + head_size)).collect();
+        let cue_points: Vec<_> = cue_points.into_iter().map(|(time, offset)| (time, offset + head_size)).collect();
+        let mut body = encode_seek_head(&seek_entries);
+        body.extend(rest);
+        if !cue_points.is_empty() {
+            body.extend(encode_cues(&cue_points, cue_track));
+        }
+        Ok(self.with_segment_body(&body))
+    }
+
+    /// Number of the first video track, None for audio-only files
+    pub fn video_track(&self) -> Option<u64> {
+        self.tracks()
+            .into_iter()
+            .find(|track| track.kind == TrackKind::Video)
+            .map(|track| track.number)
+    }
+
+    // Track cuts and cues follow: the video, or else the first track with frames
+    fn cue_track<'b>(&self, mut blocks: impl Iterator<Item = &'b Block>) -> u64 {
+        self.video_track()
+            .or_else(|| blocks.next().map(|block| block.track))
+            .unwrap_or(1)
+    }
+
+    /// Cue points: time in timecode-scale units and cluster offset from the start of the segment body
+    pub fn cues(&self) -> Vec<(u64, u64)> {
+        let Some(cues) = self.find(id::CUES) else {
+            return Vec::new();
+        };
+        children(self.data, cues)
+            .into_iter()
+            .filter(|point| point.id == id::CUE_POINT)
+            .filter_map(|point| {
+                let fields = children(self.data, point);
+                let time = fields.iter().find(|field| field.id == id::CUE_TIME)?;
+                let positions = fields.iter().find(|field| field.id == id::CUE_TRACK_POSITIONS)?;
+                let position = children(self.data, *positions)
+                    .into_iter()
+                    .find(|field| field.id == id::CUE_CLUSTER_POSITION)?;
+                Some((read_uint(time.body(self.data)), read_uint(position.body(self.data))))
+            })
+            .collect()
+    }
+
+    /// Chapters of the first edition: start in nanoseconds and title
+    pub fn chapters(&self) -> Vec<(u64, String)> {
+        let Some(edition) = self
+            .find(id::CHAPTERS)
+            .and_then(|chapters| children(self.data, chapters).into_iter().find(|entry| entry.id == id::EDITION_ENTRY))
+        else {
+            return Vec::new();
+        };
+        children(self.data, edition)
+            .into_iter()
+            .filter(|atom| atom.id == id::CHAPTER_ATOM)
+            .filter_map(|atom| {
+                let fields = children(self.data, atom);
+                let start = fields.iter().find(|field| field.id == id::CHAPTER_TIME_START)?;
+                let title = fields
+                    .iter()
+                    .find(|field| field.id == id::CHAPTER_DISPLAY)
+                    .and_then(|display| children(self.data, *display).into_iter().find(|field| field.id == id::CHAP_STRING))
+                    .map(|title| String::from_utf8_lossy(title.body(self.data)).into_owned())
+                    .unwrap_or_default();
+                Some((read_uint(start.body(self.data)), title))
+            })
+            .collect()
+    }
+
+    /// Offset from the start of the segment body the seek head gives for an element
+    pub fn seek_position(&self, element_id: u32) -> Option<u64> {
+        let head = self.find(id::SEEK_HEAD)?;
+        children(self.data, head)
+            .into_iter()
+            .filter(|seek| seek.id == id::SEEK)
+            .find_map(|seek| {
+                let fields = children(self.data, seek);
+                let seek_id = fields.iter().find(|field| field.id == id::SEEK_ID)?;
+                if read_uint(seek_id.body(self.data)) != element_id as u64 {
+                    return None;
+                }
+                let position = fields.iter().find(|field| field.id == id::SEEK_POSITION)?;
+                Some(read_uint(position.body(self.data)))
+            })
+    }
+
+    /// Time of the last frame, in timecode-scale units
+    pub fn last_timecode(&self) -> i64 {
+        self.clusters()
+            .flat_map(|cluster| self.blocks(cluster))
+            .map(|block| block.timecode)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// End of the last frame, in timecode-scale units: its time plus the gap between frames
+    pub fn end_timecode(&self) -> i64 {
+        let blocks: Vec<Block> = self.clusters().flat_map(|cluster| self.blocks(cluster)).collect();
+        let Some(last) = blocks.iter().map(|block| block.timecode).max() else {
+            return 0;
+        };
+        let track = self.cue_track(blocks.iter());
+        last + frame_step(blocks.iter().filter(|block| block.track == track).map(|block| block.timecode))
+    }
+
+    /// Duration stored in the segment info, in timecode-scale units
+    pub fn duration(&self) -> Option<f64> {
+        let info = self.find(id::INFO)?;
+        let duration = children(self.data, info)
+            .into_iter()
+            .find(|element| element.id == id::DURATION)?;
+        match duration.body(self.data) {
+            body if body.len() == 8 => Some(f64::from_be_bytes(body.try_into().ok()?)),
+            body if body.len() == 4 => Some(f32::from_be_bytes(body.try_into().ok()?) as f64),
+            _ => None,
+        }
+    }
+
+    /// Length in milliseconds, from the stored duration or else the end of the last frame
+    pub fn duration_ms(&self) -> f64 {
+        let duration = self.duration().unwrap_or(self.end_timecode() as f64);
+        duration * self.timecode_scale() as f64 / 1_000_000.0
+    }
+
+    /// Write the file back out with a new segment body, fixing up the segment size
+    pub fn with_segment_body(&self, body: &[u8]) -> Vec<u8> {
+        let mut bytes = self.header.bytes(self.data).to_vec();
+        bytes.extend(encode_element(id::SEGMENT, body));
+        bytes
+    }
+
+    /// Segment info with its Duration set, in timecode-scale units
+    pub fn info_with_duration(&self, duration: f64) -> Result<Vec<u8>, WebmError> {
+        let info = self.find(id::INFO).ok_or(WebmError::Truncated)?;
+        let mut body = Vec::new();
+        for element in children(self.data, info) {
+            if element.id != id::DURATION {
+                body.extend_from_slice(element.bytes(self.data));
+            }
+        }
+        body.extend(encode_id(id::DURATION));
+        body.push(0x88); // 8-byte size
+        body.extend(duration.to_be_bytes());
+        Ok(encode_element(id::INFO, &body))
+    }
+}
+
+/// Make MediaRecorder output (or what was saved of it before a crash) seekable:
+/// set the duration it lacks and drop any incomplete data at the end.
+pub fn fix_duration(data: &[u8]) -> Result<Vec<u8>, WebmError> {
+    let webm = Webm::parse(data)?;
+    if webm.duration().is_some() {
+        return Ok(data.to_vec());
+    }
+    if webm.find(id::SEEK_HEAD).is_some() {
+        // Its offsets would need moving along with the info
+        return Err(WebmError::Unsupported("seek head"));
+    }
+
+    let mut body = Vec::new();
+    for element in &webm.elements {
+        if element.id == id::INFO {
+            body.extend(webm.info_with_duration(webm.end_timecode().max(0) as f64)?);
+        } else {
+            body.extend_from_slice(element.bytes(data));
+        }
+    }
+    Ok(webm.with_segment_body(&body))
+}
+
+// Shortest gap between the frame times of a track, taken as how long a frame lasts
+fn frame_step(times: impl Iterator<Item = i64>) -> i64 {
+    let mut times: Vec<i64> = times.collect();
+    times.sort_unstable();
+    times.dedup();
+    times.windows(2).map(|pair| pair[1] - pair[0]).min().unwrap_or(1).max(1)
+}
+
+/// Keep the parts of a recording in `ranges` (milliseconds, in order) and join
+/// them on one timeline, without re-encoding. Each part starts at the keyframe
+/// at or before its start, as the frames in between can't be decoded without it,
+/// so the returned parts say what was actually kept.
+pub fn cut(data: &[u8], ranges: &[(f64, f64)]) -> Result<(Vec<u8>, Vec<KeptPart>), WebmError> {
+    let webm = Webm::parse(data)?;
+    let blocks: Vec<Block> = webm.clusters().flat_map(|cluster| webm.blocks(cluster)).collect();
+    let cue_track = webm.cue_track(blocks.iter());
+    let is_cut_point = |block: &&Block| block.keyframe && block.track == cue_track;
+    let step = frame_step(blocks.iter().filter(|block| block.track == cue_track).map(|block| block.timecode));
+
+    let mut kept = Vec::new();
+    let mut parts: Vec<KeptPart> = Vec::new();
+    // Last source time written so far, and where it went on the new timeline
+    let mut written: Option<(i64, i64)> = None;
+    for &(start_ms, end_ms) in ranges {
+        let (start, end) = (webm.to_timecode(start_ms), webm.to_timecode(end_ms));
+        let cut = blocks
+            .iter()
+            .filter(is_cut_point)
+            .map(|block| block.timecode)
+            .filter(|&timecode| timecode as f64 <= start)
+            .max()
+            .or_else(|| blocks.iter().filter(is_cut_point).map(|block| block.timecode).min())
+            .unwrap_or(0);
+
+        // A part overlapping what was written carries straight on from it, others follow a frame later
+        let carries_on = written.is_some_and(|(source, _)| cut <= source);
+        let (source_base, base) = match written {
+            None => (cut, 0),
+            Some((source, time)) if carries_on => (source, time),
+            Some((_, time)) => (cut, time + step),
+        };
+        let part: Vec<Placed> = blocks
+            .iter()
+            .filter(|block| block.timecode >= cut && block.timecode as f64 <= end)
+            .filter(|block| written.is_none_or(|(source, _)| block.timecode > source))
+            .map(|block| (0, *block, base + block.timecode - source_base))
+            .collect();
+        let Some(last) = part.iter().map(|(_, block, _)| block.timecode).max() else {
+            continue;
+        };
+        written = Some((last, base + last - source_base));
+        kept.extend(part);
+
+        let source_end_ms = webm.to_ms(last + step);
+        match parts.last_mut() {
+            Some(previous) if carries_on => previous.source_end_ms = source_end_ms,
+            _ => parts.push(KeptPart {
+                source: 0,
+                source_start_ms: webm.to_ms(source_base),
+                source_end_ms,
+                start_ms: webm.to_ms(base),
+            }),
+        }
+    }
+
+    if kept.is_empty() {
+        return Err(WebmError::Unsupported("nothing left after cutting"));
+    }
+    Ok((webm.remux(&[&webm], &kept, None)?, parts))
+}
+
+/// The file with its chapters replaced, each starting at the given millisecond
+/// and named by the title. No chapters removes them.
+pub fn with_chapters(data: &[u8], chapters: &[(f64, String)]) -> Result<Vec<u8>, WebmError> {
+    let webm = Webm::parse(data)?;
+    let blocks: Vec<Placed> = webm
+        .clusters()
+        .flat_map(|cluster| webm.blocks(cluster))
+        .map(|block| (0, block, block.timecode))
+        .collect();
+    webm.remux(&[&webm], &blocks, Some(chapters))
+}
+
+// How a file's tracks differ from the first file's, if they do in a way that
+// stops their frames being joined
+fn mismatch(first: &Webm, other: &Webm) -> Option<String> {
+    let (tracks, first_tracks) = (other.tracks(), first.tracks());
+    let list = |tracks: &[Track]| tracks.iter().map(Track::description).collect::<Vec<_>>().join(", ");
+    let same_layout = tracks.len() == first_tracks.len()
+        && tracks
+            .iter()
+            .zip(&first_tracks)
+            .all(|(track, first)| (track.number, track.kind, &track.codec) == (first.number, first.kind, &first.codec));
+    if !same_layout {
+        return Some(format!("has {}, the first has {}", list(&tracks), list(&first_tracks)));
+    }
+    if other.timecode_scale() != first.timecode_scale() {
+        return Some("uses a different time scale".to_string());
+    }
+    for (track, first) in tracks.iter().zip(&first_tracks) {
+        if let (Some((width, height)), Some((first_width, first_height))) = (track.size, first.size) {
+            if (width, height) != (first_width, first_height) {
+                return Some(format!("is {width}×{height}, the first is {first_width}×{first_height}"));
+            }
+        }
+        if track.sample_rate != first.sample_rate || track.channels != first.channels {
+            let audio = |track: &Track| {
+                format!("{} Hz with {} channel(s)", track.sample_rate.unwrap_or(0), track.channels.unwrap_or(1))
+            };
+            return Some(format!("has {} audio, the first has {}", audio(track), audio(first)));
+        }
+        if track.codec_private != first.codec_private {
+            return Some(format!("has different {} settings", track.description()));
+        }
+    }
+    None
+}
+
+/// Join recordings one after another into one file, without re-encoding. They
+/// must be encoded alike; the first gives the header and tracks. Each one
+/// starts a frame after the last one ends, as the returned parts say.
+pub fn concat(files: &[&[u8]]) -> Result<(Vec<u8>, Vec<KeptPart>), WebmError> {
+    let webms = files.iter().map(|data| Webm::parse(data)).collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = webms.first() else {
+        return Err(WebmError::Unsupported("nothing to join"));
+    };
+    for (index, webm) in webms.iter().enumerate().skip(1) {
+        if let Some(difference) = mismatch(first, webm) {
+            return Err(WebmError::Mismatch { file: index, difference });
+        }
+    }
+
+    let mut joined = Vec::new();
+    let mut parts = Vec::new();
+    let mut next = 0;
+    for (index, webm) in webms.iter().enumerate() {
+        let blocks: Vec<Block> = webm.clusters().flat_map(|cluster| webm.blocks(cluster)).collect();
+        let Some(start) = blocks.iter().map(|block| block.timecode).min() else {
+            continue;
+        };
+        let end = blocks.iter().map(|block| block.timecode).max().unwrap_or(start);
+        let track = webm.cue_track(blocks.iter());
+        let step = frame_step(blocks.iter().filter(|block| block.track == track).map(|block| block.timecode));
+        joined.extend(blocks.iter().map(|block| (index, *block, next + block.timecode - start)));
+        parts.push(KeptPart {
+            source: index,
+            source_start_ms: webm.to_ms(start),
+            source_end_ms: webm.to_ms(end + step),
+            start_ms: first.to_ms(next),
+        });
+        next += end - start + step;
+    }
+
+    if joined.is_empty() {
+        return Err(WebmError::Unsupported("nothing to join"));
+    }
+    let sources: Vec<&Webm> = webms.iter().collect();
+    Ok((first.remux(&sources, &joined, None)?, parts))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub fn simple_block(track: u8, timecode: i16, keyframe: bool) -> Vec<u8> {
+        let mut body = vec![0x80 | track];
+        body.extend(timecode.to_be_bytes());
+        body.push(if keyframe { 0x80 } else { 0x00 });
+        body.extend([0xAA; 16]);
+        encode_element(id::SIMPLE_BLOCK, &body)
+    }
+
+    // Cluster of unknown size, as Chrome writes them
+    pub fn cluster(timecode: u64, blocks: &[(i16, bool)]) -> Vec<u8> {
+        let mut bytes = encode_id(id::CLUSTER);
+        bytes.push(0xFF);
+        bytes.extend(encode_uint(id::TIMECODE, timecode));
+        for &(block_timecode, keyframe) in blocks {
+            bytes.extend(simple_block(1, block_timecode, keyframe));
+        }
+        bytes
+    }
+
+    /// A small recording like MediaRecorder makes: unknown segment and cluster sizes, no duration
+    pub fn recording(clusters: &[(u64, &[(i16, bool)])]) -> Vec<u8> {
+        let mut bytes = encode_element(id::EBML, &encode_element(0x4282, b"webm"));
+        bytes.extend(encode_id(id::SEGMENT));
+        bytes.extend([0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        bytes.extend(encode_element(id::INFO, &encode_uint(id::TIMECODE_SCALE, 1_000_000)));
+        let mut tracks = Vec::new();
+        for (number, kind, codec) in [(1, 1, "V_VP9"), (2, 2, "A_OPUS")] {
+            let mut entry = encode_uint(id::TRACK_NUMBER, number);
+            entry.extend(encode_uint(id::TRACK_TYPE, kind));
+            entry.extend(encode_element(id::CODEC_ID, codec.as_bytes()));
+            tracks.extend(encode_element(id::TRACK_ENTRY, &entry));
+        }
+        bytes.extend(encode_element(id::TRACKS, &tracks));
+        for &(timecode, blocks) in clusters {
+            bytes.extend(cluster(timecode, blocks));
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_vints() {
+        assert_eq!(read_vint(&[0x81], 0), Some((1, 1, false)));
+        assert_eq!(read_vint(&[0x40, 0x02], 0), Some((2, 2, false)));
+        assert_eq!(read_vint(&[0xFF], 0), Some((127, 1, true)));
+        assert_eq!(read_vint(&[0x00], 0), None);
+        assert_eq!(read_vint(&[0x40], 0), None);
+    }
+
+    #[test]
+    fn encodes_ids() {
+        assert_eq!(encode_id(id::SEGMENT), vec![0x18, 0x53, 0x80, 0x67]);
+        assert_eq!(encode_id(id::DURATION), vec![0x44, 0x89]);
+        assert_eq!(encode_id(id::TIMECODE), vec![0xE7]);
+    }
+
+    #[test]
+    fn parses_unknown_size_clusters() {
+        let data = recording(&[(0, &[(0, true), (33, false)]), (1000, &[(0, true), (500, false)])]);
+        let webm = Webm::parse(&data).unwrap();
+        assert_eq!(webm.clusters().count(), 2);
+        let blocks: Vec<_> = webm.clusters().flat_map(|cluster| webm.blocks(cluster)).collect();
+        assert_eq!(blocks.iter().map(|block| block.timecode).collect::<Vec<_>>(), vec![0, 33, 1000, 1500]);
+        assert_eq!(blocks.iter().filter(|block| block.keyframe).count(), 2);
+        assert_eq!(webm.timecode_scale(), 1_000_000);
+        assert_eq!(webm.duration(), None);
+    }
+
+    #[test]
+    fn sets_the_duration() {
+        let data = recording(&[(0, &[(0, true), (33, false)]), (1000, &[(0, true), (500, false)])]);
+        let fixed = fix_duration(&data).unwrap();
+        let webm = Webm::parse(&fixed).unwrap();
+        // Up to the end of the last frame, at 1500, which lasts the 33 between frames
+        assert_eq!(webm.last_timecode(), 1500);
+        assert_eq!(webm.duration(), Some(1533.0));
+        assert_eq!(webm.clusters().count(), 2);
+        assert_eq!(webm.duration_ms(), 1533.0);
+
+        // Fixing again only replaces the duration
+        assert_eq!(fix_duration(&fixed).unwrap(), fixed);
+    }
+
+    #[test]
+    fn recovers_a_cut_off_recording() {
+        let data = recording(&[(0, &[(0, true), (33, false)]), (1000, &[(0, true), (500, false)])]);
+        // Cut in the middle of the last block
+        let cut = &data[..data.len() - 5];
+        let fixed = fix_duration(cut).unwrap();
+        let webm = Webm::parse(&fixed).unwrap();
+        assert_eq!(webm.duration(), Some(1033.0));
+        assert_eq!(webm.clusters().flat_map(|cluster| webm.blocks(cluster)).count(), 3);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(Webm::parse(b"not a video").unwrap_err(), WebmError::NotWebm);
+        assert_eq!(Webm::parse(&[]).unwrap_err(), WebmError::NotWebm);
+        // Cut inside the segment info
+        let data = recording(&[]);
+        let webm = Webm::parse(&data).unwrap();
+        let info = webm.find(id::INFO).unwrap();
+        assert_eq!(Webm::parse(&data[..info.end - 1]).unwrap_err(), WebmError::Truncated);
+    }
+
+    fn cut_range(data: &[u8], start_ms: f64, end_ms: f64) -> Result<Vec<u8>, WebmError> {
+        cut(data, &[(start_ms, end_ms)]).map(|(out, _)| out)
+    }
+
+    fn timecodes(data: &[u8]) -> Vec<(u64, i64)> {
+        let webm = Webm::parse(data).unwrap();
+        webm.clusters()
+            .flat_map(|cluster| webm.blocks(cluster))
+            .map(|block| (block.track, block.timecode))
+            .collect()
+    }
+
+    #[test]
+    fn reads_tracks() {
+        let data = recording(&[]);
+        let tracks = Webm::parse(&data).unwrap().tracks();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!((tracks[0].number, tracks[0].kind, tracks[0].codec.as_str()), (1, TrackKind::Video, "V_VP9"));
+        assert_eq!((tracks[1].number, tracks[1].kind), (2, TrackKind::Audio));
+    }
+
+    #[test]
+    fn trims_from_the_keyframe_before_the_start() {
+        let data = recording(&[
+            (0, &[(0, true), (500, false)]),
+            (1000, &[(0, true), (500, false)]),
+            (2000, &[(0, true), (500, false)]),
+        ]);
+        let trimmed = cut_range(&data, 1200.0, 2100.0).unwrap();
+        assert_eq!(timecodes(&trimmed), vec![(1, 0), (1, 500), (1, 1000)]);
+        let webm = Webm::parse(&trimmed).unwrap();
+        assert_eq!(webm.duration(), Some(1500.0));
+        assert_eq!(webm.clusters().count(), 2);
+    }
+
+    #[test]
+    fn trims_the_end() {
+        let data = recording(&[(0, &[(0, true), (500, false)]), (1000, &[(0, true), (500, false)])]);
+        let trimmed = cut_range(&data, 0.0, 1200.0).unwrap();
+        assert_eq!(timecodes(&trimmed), vec![(1, 0), (1, 500), (1, 1000)]);
+        assert_eq!(cut_range(&data, 0.0, 5000.0).map(|whole| timecodes(&whole)), Ok(timecodes(&data)));
+    }
+
+    #[test]
+    fn audio_keyframes_are_not_cut_points() {
+        let mut data = recording(&[(0, &[(0, true)])]);
+        // Audio blocks are all keyframes; the cut must still wait for video
+        data.extend(cluster(1000, &[]));
+        data.extend(simple_block(2, 0, true));
+        data.extend(simple_block(2, 500, true));
+        let trimmed = cut_range(&data, 1200.0, 2000.0).unwrap();
+        assert_eq!(timecodes(&trimmed), vec![(1, 0), (2, 1000), (2, 1500)]);
+    }
+
+    #[test]
+    fn trimming_everything_away_fails() {
+        let data = recording(&[(1000, &[(0, true)])]);
+        assert!(cut_range(&data, 0.0, 500.0).is_err());
+    }
+
+    // MediaRecorder layouts with placeholder frame data, see tests/fixtures/README.md
+    const CHROME: &[u8] = include_bytes!("../tests/fixtures/chrome-vp9-opus.webm");
+    const FIREFOX: &[u8] = include_bytes!("../tests/fixtures/firefox-vp8-opus.webm");
+
+    // Element found at an offset from the start of the segment body
+    fn element_at(data: &[u8], position: u64) -> Element {
+        let webm = Webm::parse(data).unwrap();
+        read_element(data, webm.segment.data_start + position as usize, data.len()).unwrap()
+    }
+
+    // Largest jump between consecutive frames of a track, failing if time goes backwards
+    fn largest_gap(data: &[u8], track: u64) -> i64 {
+        let times: Vec<i64> = timecodes(data)
+            .into_iter()
+            .filter(|&(block_track, _)| block_track == track)
+            .map(|(_, timecode)| timecode)
+            .collect();
+        assert!(times.windows(2).all(|pair| pair[0] < pair[1]), "track {track} out of order");
+        times.windows(2).map(|pair| pair[1] - pair[0]).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn reads_the_fixtures() {
+        let chrome = Webm::parse(CHROME).unwrap();
+        let tracks: Vec<_> = chrome.tracks().into_iter().map(|track| (track.number, track.kind, track.codec)).collect();
+        assert_eq!(
+            tracks,
+            vec![(1, TrackKind::Audio, "A_OPUS".to_string()), (2, TrackKind::Video, "V_VP9".to_string())]
+        );
+        assert_eq!(chrome.video_track(), Some(2));
+        // Last frame at 5980, with the video at 30 frames a second
+        assert_eq!(chrome.last_timecode(), 5980);
+        assert_eq!(Webm::parse(&fix_duration(CHROME).unwrap()).unwrap().duration_ms(), 6013.0);
+
+        let firefox = Webm::parse(FIREFOX).unwrap();
+        assert_eq!(firefox.video_track(), Some(1));
+        let inter_frames = firefox
+            .clusters()
+            .flat_map(|cluster| firefox.blocks(cluster))
+            .filter(|block| block.track == 1 && !block.keyframe)
+            .count();
+        assert_eq!(inter_frames, 116);
+        assert_eq!(Webm::parse(&fix_duration(FIREFOX).unwrap()).unwrap().duration_ms(), 4013.0);
+    }
+
+    #[test]
+    fn cuts_out_the_middle() {
+        // 2.5 s to 4.2 s goes; the second part starts at the keyframe at 4 s
+        let out = cut(CHROME, &[(0.0, 2500.0), (4200.0, 6000.0)]).unwrap().0;
+        let times = timecodes(&out);
+        assert!(times.contains(&(2, 2500)));
+        assert!(times.contains(&(2, 2533)));
+        // One frame step across the join, none of the removed part left
+        assert_eq!(largest_gap(&out, 2), 34);
+        assert!(largest_gap(&out, 1) <= 33);
+        let webm = Webm::parse(&out).unwrap();
+        assert_eq!(webm.duration(), Some(4546.0));
+        assert_eq!(fix_duration(&out), Ok(out.clone()));
+    }
+
+    #[test]
+    fn joins_overlapping_parts() {
+        // The second part's keyframe lies inside the first part, so it carries straight on
+        let (out, parts) = cut(CHROME, &[(0.0, 2500.0), (2600.0, 3000.0)]).unwrap();
+        let (whole, whole_parts) = cut(CHROME, &[(0.0, 3000.0)]).unwrap();
+        assert_eq!(timecodes(&out), timecodes(&whole));
+        assert_eq!(parts, whole_parts);
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn cues_point_at_keyframe_clusters() {
+        // The first part starts back at 0, the keyframe before 1 s
+        let (out, parts) = cut(CHROME, &[(1000.0, 2500.0), (4200.0, 6000.0)]).unwrap();
+        let webm = Webm::parse(&out).unwrap();
+        let starts: Vec<_> = parts.iter().map(|part| (part.source_start_ms, part.start_ms)).collect();
+        assert_eq!(starts, vec![(0.0, 0.0), (4000.0, 2533.0)]);
+        let cues = webm.cues();
+        assert_eq!(cues.iter().map(|&(time, _)| time).collect::<Vec<_>>(), vec![0, 2000, 2533]);
+        for (time, position) in cues {
+            let cluster = element_at(&out, position);
+            assert_eq!(cluster.id, id::CLUSTER);
+            assert_eq!(webm.cluster_timecode(cluster), time);
+            let first_video = webm.blocks(cluster).into_iter().find(|block| block.track == 2).unwrap();
+            assert!(first_video.keyframe);
+            assert_eq!(first_video.timecode as u64, time);
+        }
+    }
+
+    #[test]
+    fn seek_head_points_at_the_elements() {
+        let out = cut(FIREFOX, &[(500.0, 3500.0)]).unwrap().0;
+        let webm = Webm::parse(&out).unwrap();
+        for element_id in [id::INFO, id::TRACKS, id::CUES] {
+            let position = webm.seek_position(element_id).unwrap();
+            assert_eq!(element_at(&out, position).id, element_id);
+        }
+    }
+
+    #[test]
+    fn cuts_block_groups() {
+        let out = cut(FIREFOX, &[(1500.0, 2500.0)]).unwrap().0;
+        let webm = Webm::parse(&out).unwrap();
+        let blocks: Vec<Block> = webm.clusters().flat_map(|cluster| webm.blocks(cluster)).collect();
+        let first_video = blocks.iter().find(|block| block.track == 1).unwrap();
+        assert!(first_video.keyframe);
+        assert_eq!(first_video.timecode, 0);
+        assert!(blocks.iter().any(|block| block.track == 1 && !block.keyframe));
+        assert_eq!(webm.duration(), Some(1533.0));
+        assert_eq!(largest_gap(&out, 1), 34);
+    }
+
+    #[test]
+    fn cuts_a_recovered_recording() {
+        // Cut off partway through a block, as after a crash
+        let crashed = &CHROME[..CHROME.len() - 7];
+        let recovered = fix_duration(crashed).unwrap();
+        assert_eq!(Webm::parse(&recovered).unwrap().duration_ms(), 6000.0);
+        let out = cut(&recovered, &[(0.0, 1000.0), (5000.0, 6000.0)]).unwrap().0;
+        assert_eq!(largest_gap(&out, 2), 34);
+        assert!(!Webm::parse(&out).unwrap().cues().is_empty());
+    }
+
+    #[test]
+    fn joins_takes_on_one_timeline() {
+        let (joined, parts) = concat(&[CHROME, CHROME]).unwrap();
+        let webm = Webm::parse(&joined).unwrap();
+        let second = parts[1];
+        assert_eq!((parts[0].source_start_ms, parts[0].start_ms), (0.0, 0.0));
+        assert_eq!((second.source, second.source_start_ms, second.start_ms), (1, 0.0, 6013.0));
+        assert_eq!(parts[0].source_end_ms, second.start_ms);
+        assert_eq!(second.place(1000.0), 7013.0);
+        // The second take starts one video frame after the first one's last audio block
+        assert!(timecodes(&joined).contains(&(2, 6013)));
+        // Each take lasts up to the end of its last frame, so twice one take
+        assert_eq!(webm.duration(), Some(2.0 * second.start_ms));
+        assert_eq!(largest_gap(&joined, 2), 46);
+        assert_eq!(largest_gap(&joined, 1), 33);
+
+        let cues = webm.cues();
+        assert_eq!(cues.iter().map(|&(time, _)| time).collect::<Vec<_>>(), vec![0, 2000, 4000, 6013, 8013, 10_013]);
+        for (time, position) in cues {
+            let cluster = element_at(&joined, position);
+            assert_eq!((cluster.id, webm.cluster_timecode(cluster)), (id::CLUSTER, time));
+        }
+        assert_eq!(concat(&[FIREFOX]).map(|(one, _)| timecodes(&one)), Ok(timecodes(FIREFOX)));
+    }
+
+    #[test]
+    fn refuses_to_join_different_encodings() {
+        assert_eq!(
+            concat(&[CHROME, FIREFOX]).unwrap_err().to_string(),
+            "recording 2 has VP8 video, Opus audio, the first has Opus audio, VP9 video"
+        );
+
+        // Same codecs at 1920 wide instead of 1280
+        let mut wider = CHROME.to_vec();
+        let width = wider.windows(4).position(|bytes| bytes == [0xB0, 0x82, 0x05, 0x00]).unwrap();
+        wider[width + 2..width + 4].copy_from_slice(&1920u16.to_be_bytes());
+        assert_eq!(
+            concat(&[CHROME, &wider]),
+            Err(WebmError::Mismatch { file: 1, difference: "is 1920×720, the first is 1280×720".into() })
+        );
+    }
+
+    #[test]
+    fn writes_chapters() {
+        let chapters = [(0.0, "Intro".to_string()), (2_500.0, "Démo".to_string())];
+        let out = with_chapters(CHROME, &chapters).unwrap();
+        let webm = Webm::parse(&out).unwrap();
+        assert_eq!(webm.chapters(), vec![(0, "Intro".to_string()), (2_500_000_000, "Démo".to_string())]);
+        assert_eq!(element_at(&out, webm.seek_position(id::CHAPTERS).unwrap()).id, id::CHAPTERS);
+        assert_eq!(timecodes(&out), timecodes(CHROME));
+        assert_eq!(webm.duration(), Some(6013.0));
+
+        // Written again, they replace the old ones
+        let again = with_chapters(&out, &chapters[1..]).unwrap();
+        assert_eq!(Webm::parse(&again).unwrap().chapters().len(), 1);
+        assert!(Webm::parse(&with_chapters(&out, &[]).unwrap()).unwrap().chapters().is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+//! Recording checks that need a real browser. Run them headless in each
+//! supported browser with wasm-pack:
+//!
+//! ```bash
+//! wasm-pack test --headless --chrome
+//! wasm-pack test --headless --firefox
+//! wasm-pack test --headless --safari
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+use demrec::capability::{self, Capabilities};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn can_record() {
+    let capabilities = Capabilities::detect();
+    assert!(capabilities.can_record(), "{capabilities:?}");
+}
+
+#[wasm_bindgen_test]
+fn records_webm_or_mp4() {
+    let capabilities = Capabilities::detect();
+    let mime_type = capabilities.recording_mime_type().unwrap_or_default();
+    assert!(
+        matches!(capability::container_type(mime_type), "video/webm" | "video/mp4"),
+        "{mime_type}"
+    );
+}
+
+#[wasm_bindgen_test]
+fn captures_camera_and_screen() {
+    let capabilities = Capabilities::detect();
+    assert!(capabilities.user_media);
+    assert!(capabilities.display_media);
+}
+
+#[wasm_bindgen_test]
+async fn records_a_canvas_in_the_chosen_format() {
+    let mime_type = Capabilities::detect()
+        .recording_mime_type()
+        .expect("no recording format")
+        .to_string();
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas: web_sys::HtmlCanvasElement = document.create_element("canvas").unwrap().dyn_into().unwrap();
+    canvas.set_width(64);
+    canvas.set_height(64);
+    let ctx: web_sys::CanvasRenderingContext2d = canvas.get_context("2d").unwrap().unwrap().dyn_into().unwrap();
+
+    let options = web_sys::MediaRecorderOptions::new();
+    options.set_mime_type(&mime_type);
+    let stream = canvas.capture_stream().unwrap();
+    let recorder = web_sys::MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options).unwrap();
+
+    // Resolves with the recorded data once the recorder is stopped
+    let data = js_sys::Promise::new(&mut |resolve, _reject| {
+        let handler = Closure::once_into_js(move |event: web_sys::BlobEvent| {
+            let data = event.data().map(JsValue::from).unwrap_or(JsValue::NULL);
+            let _ = resolve.call1(&JsValue::NULL, &data);
+        });
+        recorder.set_ondataavailable(Some(handler.unchecked_ref()));
+    });
+
+    recorder.start().unwrap();
+    for frame in 0..10 {
+        ctx.set_fill_style_str(if frame % 2 == 0 { "red" } else { "blue" });
+        ctx.fill_rect(0.0, 0.0, 64.0, 64.0);
+        gloo_timers::future::TimeoutFuture::new(50).await;
+    }
+    recorder.stop().unwrap();
+
+    let blob: web_sys::Blob = wasm_bindgen_futures::JsFuture::from(data)
+        .await
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    assert!(blob.size() > 0.0);
+    assert!(blob.type_().starts_with(capability::container_type(&mime_type)));
+}